@@ -1,3 +1,4 @@
 pub mod oracle;
 pub mod publisher;
+pub mod scripts;
 pub mod utils;
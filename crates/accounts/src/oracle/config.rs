@@ -0,0 +1,292 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::Context;
+use miden_crypto::{
+    dsa::rpo_falcon512::{PublicKey, SecretKey},
+    utils::Deserializable,
+};
+use miden_objects::accounts::AccountId;
+use serde::{Deserialize, Serialize};
+
+use pm_types::Pair;
+
+use crate::utils::public_key_from_hex;
+
+/// Decimals of the pairs listed without any detail in the config.
+pub const DEFAULT_PAIR_DECIMALS: u32 = 8;
+
+fn default_quorum() -> u32 {
+    1
+}
+
+fn default_pair_decimals() -> u32 {
+    DEFAULT_PAIR_DECIMALS
+}
+
+/// A pair supported by the Oracle, either only its name (format example: "BTC/USD") or a table
+/// with its decimals & publishers. Every publisher of the config is allowed when none is listed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PairConfig {
+    Name(String),
+    Detailed {
+        pair: String,
+        #[serde(default = "default_pair_decimals")]
+        decimals: u32,
+        #[serde(default)]
+        publishers: Option<Vec<String>>,
+    },
+}
+
+/// A pair of the config, converted to its on-chain representation.
+#[derive(Debug, Clone)]
+pub struct OraclePair {
+    pub pair: Pair,
+    pub decimals: u32,
+    pub publishers: Vec<AccountId>,
+}
+
+/// Describes the initial state of an Oracle account.
+///
+/// Can be loaded from a TOML or a JSON file, example:
+/// ```toml
+/// owner_key = "oracle_owner.key"
+/// quorum = 2
+/// publishers = ["0x9ae8f6e86b1d7ec1", "0x9c5b2d0f1a3e4b77"]
+/// pairs = [
+///     "BTC/USD",
+///     { pair = "ETH/USD", decimals = 6, publishers = ["0x9ae8f6e86b1d7ec1"] },
+/// ]
+/// admins = ["0x<public key>", "0x<public key>", "0x<public key>"]
+/// admin_threshold = 2
+/// max_deviation_bps = 500
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OracleConfig {
+    /// Path of a file containing the hex encoded Falcon secret key of the owner.
+    /// A new key is generated when not provided.
+    #[serde(default)]
+    pub owner_key: Option<PathBuf>,
+    /// Publishers registered at genesis, as hex account ids.
+    #[serde(default)]
+    pub publishers: Vec<String>,
+    /// Pairs supported by the Oracle.
+    #[serde(default)]
+    pub pairs: Vec<PairConfig>,
+    /// Minimum number of publishers entries required to compute a median.
+    #[serde(default = "default_quorum")]
+    pub quorum: u32,
+    /// Public keys of the admins, as hex words.
+    #[serde(default)]
+    pub admins: Vec<String>,
+    /// Number of admins that must sign the administration transactions.
+    /// The owner key is used while it is 0.
+    #[serde(default)]
+    pub admin_threshold: u32,
+    /// Maximum move of a checked median compared to the last accepted one, in basis points.
+    /// The check is disabled while it is 0.
+    #[serde(default)]
+    pub max_deviation_bps: u32,
+}
+
+impl Default for OracleConfig {
+    fn default() -> Self {
+        Self {
+            owner_key: None,
+            publishers: Vec::new(),
+            pairs: Vec::new(),
+            quorum: default_quorum(),
+            admins: Vec::new(),
+            admin_threshold: 0,
+            max_deviation_bps: 0,
+        }
+    }
+}
+
+impl OracleConfig {
+    /// Loads the config from a file, the format is deduced from the extension (`.toml` or `.json`).
+    /// A relative `owner_key` path is resolved against the directory of the config file.
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Could not read the oracle config {}", path.display()))?;
+
+        let mut config = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml_str(&content)?,
+            Some("json") => Self::from_json_str(&content)?,
+            _ => anyhow::bail!(
+                "Unsupported oracle config format for {}, expected a .toml or .json file",
+                path.display()
+            ),
+        };
+
+        if let (Some(owner_key), Some(parent)) = (&config.owner_key, path.parent()) {
+            if owner_key.is_relative() {
+                config.owner_key = Some(parent.join(owner_key));
+            }
+        }
+
+        Ok(config)
+    }
+
+    pub fn from_toml_str(content: &str) -> anyhow::Result<Self> {
+        let config: Self = toml::from_str(content).context("Invalid TOML oracle config")?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn from_json_str(content: &str) -> anyhow::Result<Self> {
+        let config: Self = serde_json::from_str(content).context("Invalid JSON oracle config")?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks that every field can be converted to its on-chain representation.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.quorum == 0 {
+            anyhow::bail!("The quorum must be at least 1");
+        }
+        let publishers = self.publisher_ids()?;
+        for (i, publisher) in publishers.iter().enumerate() {
+            if publishers[..i].contains(publisher) {
+                anyhow::bail!("Publisher {} is listed twice", publisher);
+            }
+        }
+        let pairs = self.pairs()?;
+        for (i, pair) in pairs.iter().enumerate() {
+            if pairs[..i]
+                .iter()
+                .any(|other| other.pair.to_word() == pair.pair.to_word())
+            {
+                anyhow::bail!("Pair {} is listed twice", pair.pair);
+            }
+            if let Some(publisher) = pair.publishers.iter().find(|p| !publishers.contains(*p)) {
+                anyhow::bail!(
+                    "Publisher {} of the pair {} is not in the publishers list",
+                    publisher,
+                    pair.pair
+                );
+            }
+        }
+        let admins = self.admin_public_keys()?;
+        if self.admin_threshold as usize > admins.len() {
+            anyhow::bail!(
+                "The admin threshold ({}) can't be greater than the number of admins ({})",
+                self.admin_threshold,
+                admins.len()
+            );
+        }
+        Ok(())
+    }
+
+    pub fn publisher_ids(&self) -> anyhow::Result<Vec<AccountId>> {
+        self.publishers
+            .iter()
+            .map(|id| {
+                AccountId::from_hex(id)
+                    .map_err(|e| anyhow::anyhow!("Invalid publisher id {id}: {e}"))
+            })
+            .collect()
+    }
+
+    pub fn admin_public_keys(&self) -> anyhow::Result<Vec<PublicKey>> {
+        self.admins
+            .iter()
+            .map(|admin| public_key_from_hex(admin))
+            .collect()
+    }
+
+    pub fn pairs(&self) -> anyhow::Result<Vec<OraclePair>> {
+        let all_publishers = self.publisher_ids()?;
+        self.pairs
+            .iter()
+            .map(|pair_config| {
+                let (pair, decimals, publishers) = match pair_config {
+                    PairConfig::Name(pair) => (pair, DEFAULT_PAIR_DECIMALS, None),
+                    PairConfig::Detailed {
+                        pair,
+                        decimals,
+                        publishers,
+                    } => (pair, *decimals, publishers.as_ref()),
+                };
+                let publishers = match publishers {
+                    Some(publishers) => publishers
+                        .iter()
+                        .map(|id| {
+                            AccountId::from_hex(id)
+                                .map_err(|e| anyhow::anyhow!("Invalid publisher id {id}: {e}"))
+                        })
+                        .collect::<anyhow::Result<Vec<_>>>()?,
+                    None => all_publishers.clone(),
+                };
+                Ok(OraclePair {
+                    pair: Pair::from_str(pair).with_context(|| format!("Invalid pair {pair}"))?,
+                    decimals,
+                    publishers,
+                })
+            })
+            .collect()
+    }
+
+    /// Reads the owner secret key, if the config references one.
+    pub fn owner_secret_key(&self) -> anyhow::Result<Option<SecretKey>> {
+        let Some(path) = &self.owner_key else {
+            return Ok(None);
+        };
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Could not read the owner key {}", path.display()))?;
+        let bytes = hex::decode(content.trim().trim_start_matches("0x"))
+            .context("The owner key must be hex encoded")?;
+        let secret_key = SecretKey::read_from_bytes(&bytes)
+            .map_err(|e| anyhow::anyhow!("Invalid owner key: {e}"))?;
+        Ok(Some(secret_key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_oracle_config_from_toml() {
+        let config = OracleConfig::from_toml_str(
+            r#"
+            quorum = 2
+            publishers = ["0x9ae8f6e86b1d7ec1", "0x9c5b2d0f1a3e4b77"]
+            pairs = [
+                "BTC/USD",
+                { pair = "ETH/USD", decimals = 6, publishers = ["0x9ae8f6e86b1d7ec1"] },
+            ]
+            max_deviation_bps = 500
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.quorum, 2);
+        assert_eq!(config.max_deviation_bps, 500);
+        let pairs = config.pairs().unwrap();
+        assert_eq!(pairs.len(), 2);
+        assert_eq!((pairs[0].decimals, pairs[0].publishers.len()), (8, 2));
+        assert_eq!((pairs[1].decimals, pairs[1].publishers.len()), (6, 1));
+        assert_eq!(config.publisher_ids().unwrap().len(), 2);
+        assert!(config.owner_key.is_none());
+    }
+
+    #[test]
+    fn test_oracle_config_invalid() {
+        assert!(OracleConfig::from_toml_str("quorum = 0").is_err());
+        assert!(OracleConfig::from_toml_str(r#"pairs = ["BTCUSD"]"#).is_err());
+        assert!(OracleConfig::from_toml_str(
+            r#"pairs = [{ pair = "BTC/USD", publishers = ["0x9ae8f6e86b1d7ec1"] }]"#
+        )
+        .is_err());
+        assert!(OracleConfig::from_json_str(
+            r#"{ "publishers": ["0x9ae8f6e86b1d7ec1", "0x9ae8f6e86b1d7ec1"] }"#
+        )
+        .is_err());
+    }
+}
@@ -1,64 +1,334 @@
-use std::sync::Arc;
+pub mod config;
 
-use rand::Rng;
+use std::sync::Arc;
 
 use miden_assembly::{
     ast::{Module, ModuleKind},
     DefaultSourceManager, LibraryPath,
 };
-use miden_client::{auth::AuthSecretKey, crypto::FeltRng, Client};
+use miden_client::{crypto::FeltRng, Client};
 use miden_crypto::{
     dsa::rpo_falcon512::{PublicKey, SecretKey},
+    hash::rpo::{Rpo256, RpoDigest},
     Felt, Word, ZERO,
 };
-use miden_lib::{accounts::auth::RpoFalcon512, transaction::TransactionKernel};
+use miden_lib::transaction::TransactionKernel;
 use miden_objects::{
     accounts::{
-        Account, AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageSlot,
+        Account, AccountBuilder, AccountComponent, AccountId, AccountStorageMode, AccountType,
+        StorageMap, StorageSlot,
     },
     assembly::Library,
 };
+use pm_types::PublisherId;
+use rand::Rng;
+
+use crate::utils::{account_key_and_init_seed, insert_new_account, missing_procedures};
+pub use config::{OracleConfig, OraclePair, PairConfig, DEFAULT_PAIR_DECIMALS};
 
 pub const ORACLE_ACCOUNT_MASM: &str = include_str!("oracle.masm");
 
-pub fn get_oracle_component_library() -> Library {
+// STORAGE LAYOUT
+// ================================================================================================
+// Indexes are relative to the oracle component and must match the constants of `oracle.masm`.
+// Use [oracle_account_slot] to read them from the account storage.
+
+/// Index of the first oracle component slot in the account storage. The oracle has no auth
+/// component, it authenticates its administration itself, so its component comes first.
+pub const ORACLE_COMPONENT_OFFSET: u8 = 0;
+/// Number of storage slots used by the oracle component.
+pub const ORACLE_STORAGE_SIZE: u8 = 13;
+/// Value slot holding `[nb_of_publishers, 0, 0, 0]`.
+pub const PUBLISHER_COUNT_SLOT: u8 = 1;
+/// Map slot of publisher_id -> `[1, index, 0, 0]`.
+pub const PUBLISHER_REGISTRY_SLOT: u8 = 2;
+/// Map slot of `[0, 0, 0, index]` -> publisher_id.
+pub const PUBLISHERS_MAP_SLOT: u8 = 3;
+/// Value slot holding the minimum number of entries required to compute a median.
+pub const QUORUM_SLOT: u8 = 4;
+/// Value slot holding the public key of the owner.
+pub const OWNER_PUBLIC_KEY_SLOT: u8 = 5;
+/// Value slot holding `[threshold, nb_of_admins, 0, 0]`.
+pub const ADMINS_CONFIG_SLOT: u8 = 6;
+/// Map slot of admin public key -> `[1, 0, 0, 0]`.
+pub const ADMINS_MAP_SLOT: u8 = 7;
+/// Map slot of pair -> `[1, 0, 0, 0]` when the pair is paused.
+pub const PAUSED_MAP_SLOT: u8 = 8;
+/// Value slot holding the maximum deviation, in basis points, accepted by `get_median_checked`.
+pub const MAX_DEVIATION_SLOT: u8 = 9;
+/// Map slot of pair -> `[last_median, 0, 0, 0]`, the last median accepted by `get_median_checked`.
+pub const LAST_MEDIAN_MAP_SLOT: u8 = 10;
+/// Map slot of pair -> `[1, decimals, nb_of_publishers, 0]` for the supported pairs.
+pub const PAIRS_MAP_SLOT: u8 = 11;
+/// Map slot of `hash(pair, [0, 0, 0, index])` -> publisher id, see [pair_publisher_key].
+pub const PAIR_PUBLISHERS_MAP_SLOT: u8 = 12;
+
+/// Account storage index of an oracle component slot.
+pub const fn oracle_account_slot(component_slot: u8) -> u8 {
+    ORACLE_COMPONENT_OFFSET + component_slot
+}
+
+/// Key of the paused map pausing every pair of the oracle.
+pub const GLOBAL_PAUSE_KEY: Word = [ZERO; 4];
+
+/// Advice map key under which the administration procedures read the public keys of the
+/// admins signing the transaction.
+pub const ADMIN_SIGNERS_KEY: Word = [ZERO, ZERO, ZERO, Felt::new(0x61646d696e73)];
+
+// ERRORS
+// ================================================================================================
+// Codes of the assertions of `oracle.masm`, as reported by a failed transaction.
+
+/// Not enough publishers provided an entry for the pair to compute a median.
+pub const ERR_ORACLE_QUORUM_NOT_REACHED: u32 = 0x00020001;
+/// The public key is not registered as an admin of the oracle.
+pub const ERR_ORACLE_UNKNOWN_ADMIN: u32 = 0x00020003;
+/// The admin public keys must be sorted so the same admin can't sign twice.
+pub const ERR_ORACLE_ADMINS_NOT_SORTED: u32 = 0x00020004;
+/// The public key is already registered as an admin of the oracle.
+pub const ERR_ORACLE_ADMIN_ALREADY_REGISTERED: u32 = 0x00020005;
+/// The admin threshold can't be greater than the number of admins.
+pub const ERR_ORACLE_ADMIN_THRESHOLD_TOO_HIGH: u32 = 0x00020006;
+/// The oracle is paused, no price can be read.
+pub const ERR_ORACLE_PAUSED: u32 = 0x00020007;
+/// The pair is paused, its prices can't be read.
+pub const ERR_ORACLE_PAIR_PAUSED: u32 = 0x00020008;
+/// The median moved more than the max deviation from the last accepted median.
+pub const ERR_ORACLE_DEVIATION_TOO_HIGH: u32 = 0x00020009;
+/// The pair is not supported by the oracle.
+pub const ERR_ORACLE_UNKNOWN_PAIR: u32 = 0x0002000A;
+/// The pair is already supported by the oracle.
+pub const ERR_ORACLE_PAIR_ALREADY_REGISTERED: u32 = 0x0002000B;
+/// The publisher is not registered in the oracle.
+pub const ERR_ORACLE_UNKNOWN_PUBLISHER: u32 = 0x0002000C;
+/// The publisher is already allowed to publish the pair.
+pub const ERR_ORACLE_PUBLISHER_ALREADY_IN_PAIR: u32 = 0x0002000D;
+/// The publisher is already registered in the oracle.
+pub const ERR_ORACLE_PUBLISHER_ALREADY_REGISTERED: u32 = 0x0002000E;
+
+/// Builds the storage of an oracle with the provided publishers already registered.
+pub fn oracle_storage_slots(
+    publishers: &[AccountId],
+    quorum: u32,
+) -> anyhow::Result<Vec<StorageSlot>> {
+    let mut slots = empty_oracle_storage_slots(quorum);
+    set_publisher_slots(&mut slots, publishers)?;
+    Ok(slots)
+}
+
+/// Storage of an oracle without publishers, pairs nor admins.
+fn empty_oracle_storage_slots(quorum: u32) -> Vec<StorageSlot> {
+    let mut slots: Vec<StorageSlot> = (0..ORACLE_STORAGE_SIZE)
+        .map(|_| StorageSlot::empty_value())
+        .collect();
+
+    // TODO: For some reasons, we have to add this map at index 0.
+    slots[0] = StorageSlot::empty_map();
+
+    slots[PUBLISHER_REGISTRY_SLOT as usize] = StorageSlot::empty_map();
+    slots[PUBLISHERS_MAP_SLOT as usize] = StorageSlot::empty_map();
+    slots[ADMINS_MAP_SLOT as usize] = StorageSlot::empty_map();
+    slots[PAUSED_MAP_SLOT as usize] = StorageSlot::empty_map();
+    slots[LAST_MEDIAN_MAP_SLOT as usize] = StorageSlot::empty_map();
+    slots[PAIRS_MAP_SLOT as usize] = StorageSlot::empty_map();
+    slots[PAIR_PUBLISHERS_MAP_SLOT as usize] = StorageSlot::empty_map();
+
+    slots[QUORUM_SLOT as usize] = StorageSlot::Value([Felt::from(quorum), ZERO, ZERO, ZERO]);
+
+    slots
+}
+
+/// Registers the publishers, in order, in an oracle storage built with [oracle_storage_slots].
+/// Fails if a publisher is listed twice.
+pub fn set_publisher_slots(
+    slots: &mut [StorageSlot],
+    publishers: &[AccountId],
+) -> anyhow::Result<()> {
+    ensure_oracle_storage_size(slots)?;
+
+    let mut registry_entries = Vec::with_capacity(publishers.len());
+    let mut publisher_entries = Vec::with_capacity(publishers.len());
+    for (i, publisher_id) in publishers.iter().enumerate() {
+        let publisher_id_word = PublisherId::from(*publisher_id).to_word();
+
+        registry_entries.push((
+            RpoDigest::new(publisher_id_word),
+            [Felt::new(1), Felt::new(i as u64), ZERO, ZERO],
+        ));
+        publisher_entries.push((publisher_index_key(i as u32), publisher_id_word));
+    }
+    let registry = StorageMap::with_entries(registry_entries)
+        .map_err(|e| anyhow::anyhow!("The publishers must be unique: {e}"))?;
+    let publishers_map = StorageMap::with_entries(publisher_entries)
+        .map_err(|e| anyhow::anyhow!("Invalid publisher indexes: {e}"))?;
+
+    slots[PUBLISHER_COUNT_SLOT as usize] =
+        StorageSlot::Value([Felt::new(publishers.len() as u64), ZERO, ZERO, ZERO]);
+    slots[PUBLISHER_REGISTRY_SLOT as usize] = StorageSlot::Map(registry);
+    slots[PUBLISHERS_MAP_SLOT as usize] = StorageSlot::Map(publishers_map);
+    Ok(())
+}
+
+/// Key of the `index`-th registered publisher in the publishers map.
+pub fn publisher_index_key(index: u32) -> RpoDigest {
+    RpoDigest::new([ZERO, ZERO, ZERO, Felt::from(index)])
+}
+
+/// Sets the admins of an oracle storage built with [oracle_storage_slots].
+/// Fails if an admin is listed twice or if the threshold is greater than the number of admins.
+pub fn set_admin_slots(
+    slots: &mut [StorageSlot],
+    admins: &[PublicKey],
+    threshold: u32,
+) -> anyhow::Result<()> {
+    ensure_oracle_storage_size(slots)?;
+    if threshold as usize > admins.len() {
+        anyhow::bail!(
+            "The admin threshold ({threshold}) can't be greater than the number of admins ({})",
+            admins.len()
+        );
+    }
+
+    let admin_entries = admins.iter().map(|admin| {
+        (
+            RpoDigest::from(Word::from(*admin)),
+            [Felt::new(1), ZERO, ZERO, ZERO],
+        )
+    });
+    let admins_map = StorageMap::with_entries(admin_entries)
+        .map_err(|e| anyhow::anyhow!("The admins must be unique: {e}"))?;
+
+    slots[ADMINS_MAP_SLOT as usize] = StorageSlot::Map(admins_map);
+    slots[ADMINS_CONFIG_SLOT as usize] = StorageSlot::Value([
+        Felt::from(threshold),
+        Felt::new(admins.len() as u64),
+        ZERO,
+        ZERO,
+    ]);
+    Ok(())
+}
+
+/// Key of the `index`-th publisher of a pair in the pair publishers map.
+pub fn pair_publisher_key(pair: Word, index: u32) -> RpoDigest {
+    Rpo256::merge(&[
+        RpoDigest::new(pair),
+        RpoDigest::new([ZERO, ZERO, ZERO, Felt::from(index)]),
+    ])
+}
+
+/// Adds a pair, with the publishers allowed to publish it, to an oracle storage built with
+/// [oracle_storage_slots].
+pub fn set_pair_slots(
+    slots: &mut [StorageSlot],
+    pair: Word,
+    decimals: u32,
+    publishers: &[AccountId],
+) -> anyhow::Result<()> {
+    ensure_oracle_storage_size(slots)?;
+    let StorageSlot::Map(pair_publishers) = &mut slots[PAIR_PUBLISHERS_MAP_SLOT as usize] else {
+        anyhow::bail!("The pair publishers slot of the oracle storage must be a map");
+    };
+    for (i, publisher_id) in publishers.iter().enumerate() {
+        pair_publishers.insert(
+            pair_publisher_key(pair, i as u32),
+            PublisherId::from(*publisher_id).to_word(),
+        );
+    }
+
+    let StorageSlot::Map(pairs) = &mut slots[PAIRS_MAP_SLOT as usize] else {
+        anyhow::bail!("The pairs slot of the oracle storage must be a map");
+    };
+    pairs.insert(
+        RpoDigest::new(pair),
+        [
+            Felt::new(1),
+            Felt::from(decimals),
+            Felt::new(publishers.len() as u64),
+            ZERO,
+        ],
+    );
+    Ok(())
+}
+
+fn ensure_oracle_storage_size(slots: &[StorageSlot]) -> anyhow::Result<()> {
+    if slots.len() < ORACLE_STORAGE_SIZE as usize {
+        anyhow::bail!(
+            "The oracle storage has {} slots instead of {ORACLE_STORAGE_SIZE}",
+            slots.len()
+        );
+    }
+    Ok(())
+}
+
+/// Builds the advice map entry listing the admins signing an administration transaction.
+/// The keys are sorted the way `authenticate_admin` expects them.
+pub fn admin_signers_advice(signers: &[PublicKey]) -> (RpoDigest, Vec<Felt>) {
+    let mut signers: Vec<Word> = signers.iter().map(|signer| Word::from(*signer)).collect();
+    signers.sort_by_key(|signer| signer[3].as_int());
+    (
+        RpoDigest::from(ADMIN_SIGNERS_KEY),
+        signers.into_iter().flatten().collect(),
+    )
+}
+
+pub fn get_oracle_component_library() -> anyhow::Result<Library> {
     let source_manager = Arc::new(DefaultSourceManager::default());
+    let library_path = LibraryPath::new("oracle_component::oracle_module")
+        .map_err(|e| anyhow::anyhow!("Invalid oracle library path: {e}"))?;
     let oracle_component_module = Module::parser(ModuleKind::Library)
-        .parse_str(
-            LibraryPath::new("oracle_component::oracle_module").unwrap(),
-            ORACLE_ACCOUNT_MASM,
-            &source_manager,
-        )
-        .unwrap();
+        .parse_str(library_path, ORACLE_ACCOUNT_MASM, &source_manager)
+        .map_err(|e| anyhow::anyhow!("Could not parse the oracle component: {e}"))?;
 
     TransactionKernel::testing_assembler()
         .with_debug_mode(true)
         .assemble_library([oracle_component_module])
-        .expect("assembly should succeed")
+        .map_err(|e| anyhow::anyhow!("Could not assemble the oracle component: {e}"))
+}
+
+/// Checks that an account exposes every procedure of the oracle component, so an oracle
+/// deployed elsewhere can be used by this version of the scripts.
+pub fn verify_oracle_account(account: &Account) -> anyhow::Result<()> {
+    let missing = missing_procedures(&get_oracle_component_library()?, account);
+    ensure_no_missing_procedures(account, missing)
+}
+
+/// Checks that an account exposes the provided procedures of the oracle component, for the
+/// commands only calling a few of them.
+pub fn verify_oracle_procedures(account: &Account, procedures: &[&str]) -> anyhow::Result<()> {
+    let missing = missing_procedures(&get_oracle_component_library()?, account)
+        .into_iter()
+        .filter(|procedure| procedures.contains(&procedure.as_str()))
+        .collect();
+    ensure_no_missing_procedures(account, missing)
+}
+
+fn ensure_no_missing_procedures(account: &Account, missing: Vec<String>) -> anyhow::Result<()> {
+    if !missing.is_empty() {
+        anyhow::bail!(
+            "Account {} is not a compatible oracle, missing the procedures: {}",
+            account.id(),
+            missing.join(", ")
+        );
+    }
+    Ok(())
 }
 
 pub struct OracleAccountBuilder<'a, T: FeltRng> {
     client: Option<&'a mut Client<T>>,
     account_type: AccountType,
     storage_slots: Vec<StorageSlot>,
+    secret_key: Option<SecretKey>,
+    seed: Option<[u8; 32]>,
 }
 
 impl<'a, T: FeltRng> OracleAccountBuilder<'a, T> {
     pub fn new() -> Self {
-        let default_storage_slots = {
-            let mut slots = vec![
-                StorageSlot::empty_map(),
-                StorageSlot::Value([Felt::new(3), ZERO, ZERO, ZERO]),
-                StorageSlot::empty_map(),
-            ];
-            slots.extend((0..251).map(|_| StorageSlot::empty_value()));
-            slots
-        };
-
         Self {
             client: None,
             account_type: AccountType::RegularAccountImmutableCode,
-            storage_slots: default_storage_slots,
+            storage_slots: empty_oracle_storage_slots(1),
+            secret_key: None,
+            seed: None,
         }
     }
 
@@ -72,44 +342,120 @@ impl<'a, T: FeltRng> OracleAccountBuilder<'a, T> {
         self
     }
 
+    /// Pre-registers the publishers, in order.
+    pub fn with_publishers(mut self, publishers: &[AccountId]) -> anyhow::Result<Self> {
+        set_publisher_slots(&mut self.storage_slots, publishers)?;
+        Ok(self)
+    }
+
+    /// Supports a pair, only the provided publishers are queried for its median.
+    /// The publishers must be registered with [Self::with_publishers].
+    pub fn with_pair(
+        mut self,
+        pair: Word,
+        decimals: u32,
+        publishers: &[AccountId],
+    ) -> anyhow::Result<Self> {
+        set_pair_slots(&mut self.storage_slots, pair, decimals, publishers)?;
+        Ok(self)
+    }
+
+    /// Registers the admins allowed to administrate the oracle once `threshold` of them sign.
+    /// With a threshold of 0, the owner key stays in charge.
+    pub fn with_admins(mut self, admins: &[PublicKey], threshold: u32) -> anyhow::Result<Self> {
+        set_admin_slots(&mut self.storage_slots, admins, threshold)?;
+        Ok(self)
+    }
+
+    /// Sets the maximum move, in basis points, of a median checked with `get_median_checked`
+    /// compared to the last accepted one. 0 disables the check.
+    pub fn with_max_deviation(mut self, max_deviation_bps: u32) -> anyhow::Result<Self> {
+        ensure_oracle_storage_size(&self.storage_slots)?;
+        self.storage_slots[MAX_DEVIATION_SLOT as usize] =
+            StorageSlot::Value([Felt::from(max_deviation_bps), ZERO, ZERO, ZERO]);
+        Ok(self)
+    }
+
+    /// Uses an existing Falcon key as the owner key instead of generating one.
+    pub fn with_secret_key(mut self, secret_key: SecretKey) -> Self {
+        self.secret_key = Some(secret_key);
+        self
+    }
+
+    /// Applies the publishers, pairs, quorum, admins, max deviation & owner key of an
+    /// [OracleConfig].
+    pub fn with_config(self, config: &OracleConfig) -> anyhow::Result<Self> {
+        config.validate()?;
+        let mut builder = self
+            .with_storage_slots(oracle_storage_slots(
+                &config.publisher_ids()?,
+                config.quorum,
+            )?)
+            .with_admins(&config.admin_public_keys()?, config.admin_threshold)?
+            .with_max_deviation(config.max_deviation_bps)?;
+        for pair in config.pairs()? {
+            builder = builder.with_pair(pair.pair.to_word(), pair.decimals, &pair.publishers)?;
+        }
+        if let Some(secret_key) = config.owner_secret_key()? {
+            builder = builder.with_secret_key(secret_key);
+        }
+        Ok(builder)
+    }
+
+    /// Derives the account init seed, and the owner key unless one is provided, from a seed
+    /// instead of the client rng, for reproducible account ids.
+    pub fn with_seed(mut self, seed: [u8; 32]) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
     pub fn with_client(mut self, client: &'a mut Client<T>) -> Self {
         self.client = Some(client);
         self
     }
 
-    pub async fn build(self) -> (Account, Word) {
-        let oracle_component =
-            AccountComponent::new(get_oracle_component_library(), self.storage_slots)
-                .unwrap()
-                .with_supported_type(self.account_type);
+    /// Builds the account and adds it, with its owner key, to the client. Fails if the account
+    /// is already tracked, which happens when the same seed is used twice.
+    pub async fn build(mut self) -> anyhow::Result<(Account, Word)> {
+        let client = self
+            .client
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("The oracle builder needs a Miden client"))?;
+        let (account, account_seed, private_key) = self.build_account(client.rng())?;
+
+        insert_new_account(client, &account, account_seed, private_key).await?;
+
+        Ok((account, account_seed))
+    }
 
-        let client = self.client.expect("build must have a Miden Client!");
-        let client_rng = client.rng();
-        let private_key = SecretKey::with_rng(client_rng);
+    /// Builds the account without adding it to a client, returns it with its seed & owner key.
+    /// The owner key is generated with `rng` unless provided or derived from the seed.
+    pub fn build_account<R: Rng>(
+        mut self,
+        rng: &mut R,
+    ) -> anyhow::Result<(Account, Word, SecretKey)> {
+        ensure_oracle_storage_size(&self.storage_slots)?;
+        let (private_key, from_seed) = account_key_and_init_seed(rng, self.seed, self.secret_key);
         let public_key = private_key.public_key();
 
-        let auth_component: RpoFalcon512 = RpoFalcon512::new(PublicKey::new(public_key.into()));
+        // The oracle authenticates its administration itself so the owner key can be rotated.
+        self.storage_slots[OWNER_PUBLIC_KEY_SLOT as usize] =
+            StorageSlot::Value(Word::from(public_key));
+
+        let oracle_component =
+            AccountComponent::new(get_oracle_component_library()?, self.storage_slots)
+                .map_err(|e| anyhow::anyhow!("Invalid oracle component: {e}"))?
+                .with_supported_type(self.account_type);
 
-        let from_seed = client_rng.gen();
         let (account, account_seed) = AccountBuilder::new()
             .init_seed(from_seed)
             .account_type(self.account_type)
             .storage_mode(AccountStorageMode::Public)
-            .with_component(auth_component)
             .with_component(oracle_component)
             .build()
-            .unwrap();
-
-        client
-            .insert_account(
-                &account,
-                Some(account_seed),
-                &AuthSecretKey::RpoFalcon512(private_key),
-            )
-            .await
-            .unwrap();
-
-        (account, account_seed)
+            .map_err(|e| anyhow::anyhow!("Could not build the oracle account: {e}"))?;
+
+        Ok((account, account_seed, private_key))
     }
 }
 
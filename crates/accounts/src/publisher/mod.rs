@@ -1,44 +1,77 @@
 use std::sync::Arc;
 
-use rand::Rng;
-
 use miden_assembly::{
     ast::{Module, ModuleKind},
     DefaultSourceManager, LibraryPath,
 };
-use miden_client::{accounts::AccountStorageMode, auth::AuthSecretKey, crypto::FeltRng, Client};
+use miden_client::{accounts::AccountStorageMode, crypto::FeltRng, Client};
 use miden_crypto::{
     dsa::rpo_falcon512::{PublicKey, SecretKey},
-    Word,
+    hash::rpo::{Rpo256, RpoDigest},
+    Felt, Word,
 };
 use miden_lib::{accounts::auth::RpoFalcon512, transaction::TransactionKernel};
 use miden_objects::{
     accounts::{Account, AccountBuilder, AccountComponent, AccountType, StorageSlot},
     assembly::Library,
 };
+use pm_types::Entry;
+use rand::Rng;
+
+use crate::utils::{account_key_and_init_seed, insert_new_account};
 
 pub const PUBLISHER_ACCOUNT_MASM: &str = include_str!("publisher.masm");
 
-pub fn get_publisher_component_library() -> Library {
+/// Index of the first publisher component slot in the account storage, after the slot of the
+/// RpoFalcon512 auth component.
+pub const PUBLISHER_COMPONENT_OFFSET: u8 = 1;
+/// Map of the last entry published for each pair, keyed by `[0, 0, 0, pair]`.
+pub const ENTRIES_MAP_SLOT: u8 = 1;
+
+/// Account storage index of a publisher component slot.
+pub const fn publisher_account_slot(component_slot: u8) -> u8 {
+    PUBLISHER_COMPONENT_OFFSET + component_slot
+}
+
+/// Returns the `[PAIR, ENTRY]` words of an entry, as expected by `publish_entry`.
+/// Both words are derived from the same entry so the pair key always matches the entry pair.
+pub fn entry_to_words(entry: &Entry) -> anyhow::Result<(Word, Word)> {
+    let entry_as_word: Word = entry.clone().try_into()?;
+    Ok((entry.pair.to_word(), entry_as_word))
+}
+
+/// Builds the advice map entry read by `publish_entries`: the entries are flattened as
+/// `[PAIR, ENTRY]` words, under a key committing to them.
+pub fn entries_advice(entries: &[Entry]) -> anyhow::Result<(RpoDigest, Vec<Felt>)> {
+    let mut values = Vec::with_capacity(entries.len() * 8);
+    for entry in entries {
+        let (pair_as_word, entry_as_word) = entry_to_words(entry)?;
+        values.extend(pair_as_word);
+        values.extend(entry_as_word);
+    }
+    Ok((Rpo256::hash_elements(&values), values))
+}
+
+pub fn get_publisher_component_library() -> anyhow::Result<Library> {
     let source_manager = Arc::new(DefaultSourceManager::default());
+    let library_path = LibraryPath::new("publisher_component::publisher_module")
+        .map_err(|e| anyhow::anyhow!("Invalid publisher library path: {e}"))?;
     let publisher_component_module = Module::parser(ModuleKind::Library)
-        .parse_str(
-            LibraryPath::new("publisher_component::publisher_module").unwrap(),
-            PUBLISHER_ACCOUNT_MASM,
-            &source_manager,
-        )
-        .unwrap();
+        .parse_str(library_path, PUBLISHER_ACCOUNT_MASM, &source_manager)
+        .map_err(|e| anyhow::anyhow!("Could not parse the publisher component: {e}"))?;
 
     TransactionKernel::testing_assembler()
         .with_debug_mode(true)
         .assemble_library([publisher_component_module])
-        .expect("assembly should succeed")
+        .map_err(|e| anyhow::anyhow!("Could not assemble the publisher component: {e}"))
 }
 
 pub struct PublisherAccountBuilder<'a, T: FeltRng> {
     client: Option<&'a mut Client<T>>,
     account_type: AccountType,
     storage_slots: Vec<StorageSlot>,
+    secret_key: Option<SecretKey>,
+    seed: Option<[u8; 32]>,
 }
 
 impl<'a, T: FeltRng> PublisherAccountBuilder<'a, T> {
@@ -48,6 +81,8 @@ impl<'a, T: FeltRng> PublisherAccountBuilder<'a, T> {
             client: None,
             account_type: AccountType::RegularAccountImmutableCode,
             storage_slots: default_storage_slots,
+            secret_key: None,
+            seed: None,
         }
     }
 
@@ -61,25 +96,55 @@ impl<'a, T: FeltRng> PublisherAccountBuilder<'a, T> {
         self
     }
 
+    /// Uses an existing Falcon key to authenticate the publisher instead of generating one.
+    pub fn with_secret_key(mut self, secret_key: SecretKey) -> Self {
+        self.secret_key = Some(secret_key);
+        self
+    }
+
+    /// Derives the account init seed, and the key unless one is provided, from a seed
+    /// instead of the client rng, for reproducible account ids.
+    pub fn with_seed(mut self, seed: [u8; 32]) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
     pub fn with_client(mut self, client: &'a mut Client<T>) -> Self {
         self.client = Some(client);
         self
     }
 
-    pub async fn build(self) -> (Account, Word) {
+    /// Builds the account and adds it, with its key, to the client. Fails if the account is
+    /// already tracked, which happens when the same seed is used twice.
+    pub async fn build(mut self) -> anyhow::Result<(Account, Word)> {
+        let client = self
+            .client
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("The publisher builder needs a Miden client"))?;
+        let (account, account_seed, private_key) = self.build_account(client.rng())?;
+
+        insert_new_account(client, &account, account_seed, private_key).await?;
+        client
+            .sync_state()
+            .await
+            .map_err(|e| anyhow::anyhow!("Could not sync the client: {e:?}"))?;
+
+        Ok((account, account_seed))
+    }
+
+    /// Builds the account without adding it to a client, returns it with its seed & key.
+    /// The key is generated with `rng` unless provided or derived from the seed.
+    pub fn build_account<R: Rng>(self, rng: &mut R) -> anyhow::Result<(Account, Word, SecretKey)> {
         let publisher_component =
-            AccountComponent::new(get_publisher_component_library(), self.storage_slots)
-                .unwrap()
+            AccountComponent::new(get_publisher_component_library()?, self.storage_slots)
+                .map_err(|e| anyhow::anyhow!("Invalid publisher component: {e}"))?
                 .with_supported_type(self.account_type);
 
-        let client = self.client.expect("build must have a Miden Client!");
-        let client_rng = client.rng();
-        let private_key = SecretKey::with_rng(client_rng);
+        let (private_key, from_seed) = account_key_and_init_seed(rng, self.seed, self.secret_key);
         let public_key = private_key.public_key();
 
         let auth_component: RpoFalcon512 = RpoFalcon512::new(PublicKey::new(public_key.into()));
 
-        let from_seed = client_rng.gen();
         let (account, account_seed) = AccountBuilder::new()
             .init_seed(from_seed)
             .account_type(self.account_type)
@@ -87,19 +152,9 @@ impl<'a, T: FeltRng> PublisherAccountBuilder<'a, T> {
             .with_component(auth_component)
             .with_component(publisher_component)
             .build()
-            .unwrap();
-
-        client
-            .insert_account(
-                &account,
-                Some(account_seed),
-                &AuthSecretKey::RpoFalcon512(private_key),
-            )
-            .await
-            .unwrap();
-        client.sync_state().await.unwrap();
+            .map_err(|e| anyhow::anyhow!("Could not build the publisher account: {e}"))?;
 
-        (account, account_seed)
+        Ok((account, account_seed, private_key))
     }
 }
 
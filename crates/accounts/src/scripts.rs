@@ -0,0 +1,241 @@
+//! Transaction scripts calling the oracle and publisher procedures.
+//!
+//! Scripts are built from typed inputs so callers don't have to care about the order in which
+//! the words must be pushed on the stack.
+
+use miden_crypto::{dsa::rpo_falcon512::PublicKey, hash::rpo::RpoDigest, Felt, Word};
+use miden_lib::transaction::TransactionKernel;
+use miden_objects::{assembly::Library, transaction::TransactionScript};
+use pm_types::{Entry, PublisherId};
+
+use crate::{
+    oracle::get_oracle_component_library,
+    publisher::{entry_to_words, get_publisher_component_library},
+    utils::word_to_masm,
+};
+
+// ERRORS
+// ================================================================================================
+
+/// The median returned by the oracle differs from the expected one, see [expected_median_script].
+pub const ERR_MEDIAN_MISMATCH: u32 = 0x00040001;
+
+// PUBLISHER SCRIPTS
+// ================================================================================================
+
+/// Script publishing a single entry. `publish_entry` authenticates the publisher itself.
+pub fn publish_entry_script(entry: &Entry) -> anyhow::Result<String> {
+    let (pair_as_word, entry_as_word) = entry_to_words(entry)?;
+    Ok(publisher_script(
+        "publish_entry",
+        &format!(
+            "{}.{}",
+            word_to_masm(entry_as_word),
+            word_to_masm(pair_as_word)
+        ),
+    ))
+}
+
+/// Script publishing the entries stored in the advice map under `entries_key`, see
+/// [crate::publisher::entries_advice].
+pub fn publish_entries_script(entries_key: RpoDigest, nb_of_entries: usize) -> String {
+    publisher_script(
+        "publish_entries",
+        &format!("{}.{}", nb_of_entries, word_to_masm(entries_key.into())),
+    )
+}
+
+/// Script reading the entry of a pair from a publisher.
+pub fn publisher_get_entry_script(pair: Word) -> String {
+    publisher_script("get_entry", &word_to_masm(pair))
+}
+
+// ORACLE SCRIPTS
+// ================================================================================================
+
+/// Script registering a publisher into the oracle.
+pub fn register_publisher_script(publisher_id: PublisherId) -> String {
+    oracle_script("register_publisher", &word_to_masm(publisher_id.to_word()))
+}
+
+/// Script reading the entry of a pair published by a publisher, through the oracle.
+pub fn oracle_get_entry_script(publisher_id: PublisherId, pair: Word) -> String {
+    oracle_script(
+        "get_entry",
+        &format!(
+            "{}.{}",
+            word_to_masm(pair),
+            word_to_masm(publisher_id.to_word())
+        ),
+    )
+}
+
+/// Script computing the median of a pair. The checked version fails if the median moved more
+/// than the max deviation, and stores it as the last checked median.
+/// `get_median_checked` must be signed by the oracle administration, see
+/// [crate::oracle::admin_signers_advice].
+pub fn median_script(pair: Word, checked: bool) -> String {
+    oracle_script(median_procedure(checked), &word_to_masm(pair))
+}
+
+/// Script computing the median of a pair like [median_script], failing with
+/// [ERR_MEDIAN_MISMATCH] unless the oracle returns `expected_median`. Used to check a median
+/// computed off-chain against the one of the oracle.
+pub fn expected_median_script(pair: Word, checked: bool, expected_median: u64) -> String {
+    format!(
+        "
+        use.oracle_component::oracle_module
+        use.std::sys
+
+        const.ERR_MEDIAN_MISMATCH={ERR_MEDIAN_MISMATCH:#010x}
+
+        begin
+            push.{pair}
+            call.oracle_module::{procedure}
+            push.{expected_median} assert_eq.err=ERR_MEDIAN_MISMATCH
+            exec.sys::truncate_stack
+        end
+        ",
+        pair = word_to_masm(pair),
+        procedure = median_procedure(checked),
+    )
+}
+
+/// Script reading the config of a pair, see `get_pair`.
+pub fn get_pair_script(pair: Word) -> String {
+    oracle_script("get_pair", &word_to_masm(pair))
+}
+
+// ORACLE ADMINISTRATION SCRIPTS
+// ================================================================================================
+// The administration procedures authenticate the owner, or the admins listed in the advice map
+// with [crate::oracle::admin_signers_advice], themselves.
+
+/// Script replacing the owner public key of the oracle.
+pub fn transfer_ownership_script(new_owner: PublicKey) -> String {
+    oracle_script("transfer_ownership", &word_to_masm(new_owner.into()))
+}
+
+/// Script registering an admin public key.
+pub fn add_admin_script(admin: PublicKey) -> String {
+    oracle_script("add_admin", &word_to_masm(admin.into()))
+}
+
+/// Script removing an admin public key.
+pub fn remove_admin_script(admin: PublicKey) -> String {
+    oracle_script("remove_admin", &word_to_masm(admin.into()))
+}
+
+/// Script setting the number of admins required to sign, 0 giving the control back to the owner.
+pub fn set_admin_threshold_script(threshold: u32) -> String {
+    oracle_script("set_admin_threshold", &threshold.to_string())
+}
+
+/// Script pausing the reads of a pair, or of every pair with [crate::oracle::GLOBAL_PAUSE_KEY].
+pub fn pause_script(pause_key: Word) -> String {
+    oracle_script("pause", &word_to_masm(pause_key))
+}
+
+/// Script resuming the reads of a pair, or lifting the global pause with
+/// [crate::oracle::GLOBAL_PAUSE_KEY].
+pub fn unpause_script(pause_key: Word) -> String {
+    oracle_script("unpause", &word_to_masm(pause_key))
+}
+
+/// Script setting the max deviation, in basis points, checked by `get_median_checked`.
+pub fn set_max_deviation_script(max_deviation_bps: u32) -> String {
+    oracle_script("set_max_deviation", &max_deviation_bps.to_string())
+}
+
+/// Script adding a pair to the oracle, without any publisher allowed to publish it.
+pub fn add_pair_script(pair: Word, decimals: u32) -> String {
+    oracle_script("add_pair", &format!("{}.{}", decimals, word_to_masm(pair)))
+}
+
+/// Script removing a pair from the oracle.
+pub fn remove_pair_script(pair: Word) -> String {
+    oracle_script("remove_pair", &word_to_masm(pair))
+}
+
+/// Script allowing a registered publisher to publish a pair.
+pub fn add_pair_publisher_script(pair: Word, publisher_id: PublisherId) -> String {
+    oracle_script(
+        "add_pair_publisher",
+        &format!(
+            "{}.{}",
+            word_to_masm(publisher_id.to_word()),
+            word_to_masm(pair)
+        ),
+    )
+}
+
+// COMPILATION
+// ================================================================================================
+
+/// Compiles a script calling the oracle component.
+pub fn compile_oracle_script(
+    tx_script_code: String,
+    advice: Vec<(Word, Vec<Felt>)>,
+) -> anyhow::Result<TransactionScript> {
+    compile_script(tx_script_code, advice, get_oracle_component_library()?)
+}
+
+/// Compiles a script calling the publisher component.
+pub fn compile_publisher_script(
+    tx_script_code: String,
+    advice: Vec<(Word, Vec<Felt>)>,
+) -> anyhow::Result<TransactionScript> {
+    compile_script(tx_script_code, advice, get_publisher_component_library()?)
+}
+
+fn compile_script(
+    tx_script_code: String,
+    advice: Vec<(Word, Vec<Felt>)>,
+    library: Library,
+) -> anyhow::Result<TransactionScript> {
+    TransactionScript::compile(
+        tx_script_code,
+        advice,
+        TransactionKernel::testing_assembler()
+            .with_debug_mode(true)
+            .with_library(library)
+            .map_err(|e| anyhow::anyhow!("Error while setting up the component library: {e:?}"))?
+            .clone(),
+    )
+    .map_err(|e| anyhow::anyhow!("Error while compiling the script: {e:?}"))
+}
+
+// HELPERS
+// ================================================================================================
+
+/// Name of the oracle procedure computing the median, checked or not.
+pub fn median_procedure(checked: bool) -> &'static str {
+    if checked {
+        "get_median_checked"
+    } else {
+        "get_median"
+    }
+}
+
+fn oracle_script(procedure: &str, input: &str) -> String {
+    component_script("oracle_component", "oracle_module", procedure, input)
+}
+
+fn publisher_script(procedure: &str, input: &str) -> String {
+    component_script("publisher_component", "publisher_module", procedure, input)
+}
+
+fn component_script(library: &str, module: &str, procedure: &str, input: &str) -> String {
+    format!(
+        "
+        use.{library}::{module}
+        use.std::sys
+
+        begin
+            push.{input}
+            call.{module}::{procedure}
+            exec.sys::truncate_stack
+        end
+        "
+    )
+}
@@ -1,4 +1,12 @@
-use miden_crypto::Word;
+use miden_client::{auth::AuthSecretKey, crypto::FeltRng, Client};
+use miden_crypto::{
+    dsa::rpo_falcon512::{PublicKey, SecretKey},
+    hash::rpo::RpoDigest,
+    Word,
+};
+use miden_objects::{accounts::Account, assembly::Library};
+use rand::Rng;
+use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
 
 /// Word to MASM
 pub fn word_to_masm(word: Word) -> String {
@@ -7,3 +15,78 @@ pub fn word_to_masm(word: Word) -> String {
         .collect::<Vec<_>>()
         .join(".")
 }
+
+/// Parses a Falcon public key from its hex word representation
+pub fn public_key_from_hex(hex: &str) -> anyhow::Result<PublicKey> {
+    let digest =
+        RpoDigest::try_from(hex).map_err(|e| anyhow::anyhow!("Invalid public key {hex}: {e}"))?;
+    Ok(PublicKey::new(digest.into()))
+}
+
+/// Hex representation of a Falcon public key
+pub fn public_key_to_hex(public_key: PublicKey) -> String {
+    RpoDigest::from(Word::from(public_key)).to_hex()
+}
+
+/// Names of the procedures exported by a component library that the account doesn't expose
+pub fn missing_procedures(library: &Library, account: &Account) -> Vec<String> {
+    let account_roots: Vec<RpoDigest> = account.code().procedure_roots().collect();
+    library
+        .exports()
+        .filter(|name| {
+            let root = library.mast_forest()[library.get_export_node_id(name)].digest();
+            !account_roots.contains(&root)
+        })
+        .map(|name| name.name.to_string())
+        .collect()
+}
+
+/// Returns the secret key, generated unless provided, and the init seed of a new account.
+/// With a seed, both are derived from it instead of `rng` so the same inputs always yield the
+/// same account id.
+pub fn account_key_and_init_seed<R: Rng>(
+    rng: &mut R,
+    seed: Option<[u8; 32]>,
+    secret_key: Option<SecretKey>,
+) -> (SecretKey, [u8; 32]) {
+    fn derive<R: Rng>(rng: &mut R, secret_key: Option<SecretKey>) -> (SecretKey, [u8; 32]) {
+        let secret_key = secret_key.unwrap_or_else(|| SecretKey::with_rng(rng));
+        (secret_key, rng.gen())
+    }
+
+    match seed {
+        Some(seed) => derive(&mut ChaCha20Rng::from_seed(seed), secret_key),
+        None => derive(rng, secret_key),
+    }
+}
+
+/// Adds a new account with its secret key to the client. Fails if the account is already
+/// tracked, which happens when an account is created twice from the same seed.
+pub async fn insert_new_account<T: FeltRng>(
+    client: &mut Client<T>,
+    account: &Account,
+    account_seed: Word,
+    secret_key: SecretKey,
+) -> anyhow::Result<()> {
+    if client.get_account(account.id()).await.is_ok() {
+        anyhow::bail!(
+            "Account {} already exists in the local store, it was already created from the \
+             same seed. Use another seed file, or `keys export` & `keys import` to use the \
+             account elsewhere",
+            account.id()
+        );
+    }
+    client
+        .insert_account(
+            account,
+            Some(account_seed),
+            &AuthSecretKey::RpoFalcon512(secret_key),
+        )
+        .await
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "Could not add the account {} to the local store: {e:?}",
+                account.id()
+            )
+        })
+}
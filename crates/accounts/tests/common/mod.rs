@@ -0,0 +1,284 @@
+//! Test harness shared by the account tests: deterministic keys & entries, accounts built with
+//! the real builders, and the advice inputs of foreign procedure calls.
+#![allow(dead_code)]
+
+use std::{str::FromStr, sync::Arc};
+
+use miden_crypto::{
+    dsa::rpo_falcon512::SecretKey, hash::rpo::RpoDigest, rand::RpoRandomCoin, Word, ONE, ZERO,
+};
+use miden_objects::{
+    accounts::{Account, AuthSecretKey, StorageMap, StorageSlot},
+    transaction::{ExecutedTransaction, TransactionScript},
+    vm::AdviceInputs,
+};
+use miden_processor::ExecutionError;
+use miden_tx::{
+    auth::{BasicAuthenticator, TransactionAuthenticator},
+    testing::MockChain,
+    TransactionExecutor, TransactionExecutorError,
+};
+use rand::Rng;
+use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+use pm_accounts::{oracle::OracleAccountBuilder, publisher::PublisherAccountBuilder};
+use pm_types::{Entry, Pair};
+
+pub type TestOracleBuilder = OracleAccountBuilder<'static, RpoRandomCoin>;
+pub type TestPublisherBuilder = PublisherAccountBuilder<'static, RpoRandomCoin>;
+
+// KEYS
+// ================================================================================================
+
+/// Falcon secret key derived from a seed, with an authenticator signing with it.
+pub fn new_key_and_authenticator(seed: [u8; 32]) -> (SecretKey, Arc<dyn TransactionAuthenticator>) {
+    let secret_key = SecretKey::with_rng(&mut ChaCha20Rng::from_seed(seed));
+    let authenticator = authenticator(&[secret_key.clone()]);
+    (secret_key, authenticator)
+}
+
+/// Authenticator signing with any of the provided keys.
+pub fn authenticator(secret_keys: &[SecretKey]) -> Arc<dyn TransactionAuthenticator> {
+    let keys: Vec<(Word, AuthSecretKey)> = secret_keys
+        .iter()
+        .map(|sk| {
+            (
+                sk.public_key().into(),
+                AuthSecretKey::RpoFalcon512(sk.clone()),
+            )
+        })
+        .collect();
+    Arc::new(BasicAuthenticator::<ChaCha20Rng>::new_with_rng(
+        &keys,
+        ChaCha20Rng::from_seed([0; 32]),
+    ))
+}
+
+// ACCOUNTS
+// ================================================================================================
+
+/// Builds an oracle with the real builder, its owner key & account id derived from `seed`.
+pub fn build_oracle(
+    builder: TestOracleBuilder,
+    seed: [u8; 32],
+) -> (Account, Arc<dyn TransactionAuthenticator>) {
+    let (secret_key, authenticator) = new_key_and_authenticator(seed);
+    let (account, _, _) = builder
+        .with_secret_key(secret_key)
+        .with_seed(seed)
+        .build_account(&mut ChaCha20Rng::from_seed(seed))
+        .unwrap();
+    (deployed(account), authenticator)
+}
+
+/// Builds a publisher with the real builder, its key & account id derived from `seed`.
+pub fn build_publisher(
+    builder: TestPublisherBuilder,
+    seed: [u8; 32],
+) -> (Account, Arc<dyn TransactionAuthenticator>) {
+    let (secret_key, authenticator) = new_key_and_authenticator(seed);
+    let (account, _, _) = builder
+        .with_secret_key(secret_key)
+        .with_seed(seed)
+        .build_account(&mut ChaCha20Rng::from_seed(seed))
+        .unwrap();
+    (deployed(account), authenticator)
+}
+
+/// Builds a publisher that already published the entries, its key & account id derived from
+/// `seed`.
+pub fn build_publisher_with_entries(
+    entries: &[Entry],
+    seed: [u8; 32],
+) -> (Account, Arc<dyn TransactionAuthenticator>) {
+    let entries = entries.iter().map(|entry| {
+        let entry_as_word: Word = entry.clone().try_into().unwrap();
+        (RpoDigest::new(entry.pair.to_word()), entry_as_word)
+    });
+    build_publisher(
+        TestPublisherBuilder::new().with_storage_slots(vec![
+            StorageSlot::empty_map(),
+            StorageSlot::Map(StorageMap::with_entries(entries).unwrap()),
+        ]),
+        seed,
+    )
+}
+
+/// Same account with a non-zero nonce, as if its creation was already committed on chain, so
+/// the transactions don't need its seed.
+pub fn deployed(account: Account) -> Account {
+    Account::from_parts(
+        account.id(),
+        account.vault().clone(),
+        account.storage().clone(),
+        account.code().clone(),
+        ONE,
+    )
+}
+
+// ENTRIES
+// ================================================================================================
+
+pub fn mock_pair() -> Pair {
+    Pair::from_str("BTC/USD").unwrap()
+}
+
+pub fn mock_entry() -> Entry {
+    Entry {
+        pair: mock_pair(),
+        price: 98_179_880_000_000,
+        decimals: 8,
+        timestamp: 1_733_844_099,
+    }
+}
+
+/// Entry of the mock pair with a random price, large enough to overflow a u32.
+pub fn random_entry<R: Rng>(rng: &mut R) -> Entry {
+    Entry {
+        price: rng.gen_range(90_000_00000000..110_000_00000000),
+        ..mock_entry()
+    }
+}
+
+// TRANSACTIONS
+// ================================================================================================
+
+/// Builds the advice inputs giving the transactions access to foreign accounts, as read by
+/// `tx::execute_foreign_procedure`.
+pub struct FpiAdviceBuilder<'a> {
+    mock_chain: &'a MockChain,
+    accounts: Vec<Account>,
+}
+
+impl<'a> FpiAdviceBuilder<'a> {
+    pub fn new(mock_chain: &'a MockChain) -> Self {
+        Self {
+            mock_chain,
+            accounts: Vec::new(),
+        }
+    }
+
+    pub fn with_account(&mut self, account: &Account) -> &mut Self {
+        self.accounts.push(account.clone());
+        self
+    }
+
+    pub fn build(&self) -> AdviceInputs {
+        let mut advice_inputs =
+            AdviceInputs::default().with_merkle_store(self.mock_chain.accounts().into());
+
+        for account in self.accounts.iter() {
+            let id_root = RpoDigest::new([account.id().into(), ZERO, ZERO, ZERO]);
+            let id_and_nonce = [account.id().into(), ZERO, ZERO, account.nonce()];
+            let vault_root = account.vault().commitment();
+            let storage_root = account.storage().commitment();
+            let code_root = account.code().commitment();
+
+            advice_inputs.extend_map([
+                // ACCOUNT_ID |-> [ID_AND_NONCE, VAULT_ROOT, STORAGE_ROOT, CODE_ROOT]
+                (
+                    id_root,
+                    [
+                        id_and_nonce.as_slice(),
+                        vault_root.as_elements(),
+                        storage_root.as_elements(),
+                        code_root.as_elements(),
+                    ]
+                    .concat(),
+                ),
+                // STORAGE_ROOT |-> [[STORAGE_SLOT_DATA]]
+                (storage_root, account.storage().as_elements()),
+                // CODE_ROOT |-> [[ACCOUNT_PROCEDURE_DATA]]
+                (code_root, account.code().as_elements()),
+            ]);
+
+            // The storage maps are read through their merkle paths & leaves
+            for slot in account.storage().slots() {
+                if let StorageSlot::Map(map) = slot {
+                    advice_inputs.extend_merkle_store(map.inner_nodes());
+                    advice_inputs.extend_map(
+                        map.leaves()
+                            .map(|(_, leaf)| (leaf.hash(), leaf.to_elements())),
+                    );
+                }
+            }
+        }
+
+        advice_inputs
+    }
+}
+
+/// Executes a script against an account, with the provided foreign accounts.
+pub fn execute_script(
+    account: &Account,
+    authenticator: Arc<dyn TransactionAuthenticator>,
+    tx_script: TransactionScript,
+    foreign_accounts: &[Account],
+) -> Result<ExecutedTransaction, TransactionExecutorError> {
+    let mut accounts = foreign_accounts.to_vec();
+    accounts.push(account.clone());
+    let mut mock_chain = MockChain::with_accounts(&accounts);
+    mock_chain.seal_block(None);
+
+    let mut advice_inputs_builder = FpiAdviceBuilder::new(&mock_chain);
+    for foreign_account in foreign_accounts {
+        advice_inputs_builder.with_account(foreign_account);
+    }
+    let advice_inputs = advice_inputs_builder.build();
+
+    let tx_context = mock_chain
+        .build_tx_context(account.id(), &[], &[])
+        .advice_inputs(advice_inputs)
+        .tx_script(tx_script)
+        .build();
+
+    let mut executor = TransactionExecutor::new(Arc::new(tx_context.clone()), Some(authenticator))
+        .with_debug_mode(true)
+        .with_tracing();
+
+    // load the foreign accounts code into the transaction executor
+    for foreign_account in foreign_accounts {
+        executor.load_account_code(foreign_account.code());
+    }
+
+    executor.execute_transaction(
+        account.id(),
+        tx_context.tx_inputs().block_header().block_num(),
+        &[],
+        tx_context.tx_args().clone(),
+    )
+}
+
+/// Asserts that a transaction failed on an assertion with the provided error code.
+pub fn assert_tx_error(
+    result: Result<ExecutedTransaction, TransactionExecutorError>,
+    err_code: u32,
+) {
+    match result {
+        Err(TransactionExecutorError::TransactionProgramExecutionFailed(
+            ExecutionError::FailedAssertion { err_code: code, .. },
+        )) => assert_eq!(
+            code, err_code,
+            "the transaction failed with the error code {code:#010x} instead of {err_code:#010x}"
+        ),
+        Err(err) => {
+            panic!("the transaction failed without the error code {err_code:#010x}: {err:?}")
+        }
+        Ok(_) => panic!("the transaction succeeded instead of failing with {err_code:#010x}"),
+    }
+}
+
+/// Executes a script and applies its changes to the account.
+pub fn apply_script(
+    account: &mut Account,
+    authenticator: Arc<dyn TransactionAuthenticator>,
+    tx_script: TransactionScript,
+    foreign_accounts: &[Account],
+) -> ExecutedTransaction {
+    let executed_transaction =
+        execute_script(account, authenticator, tx_script, foreign_accounts).unwrap();
+    account
+        .apply_delta(executed_transaction.account_delta())
+        .unwrap();
+    executed_transaction
+}
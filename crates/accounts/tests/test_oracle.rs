@@ -1,223 +1,571 @@
-pub mod common;
+mod common;
 
 use std::sync::Arc;
 
-use miden_crypto::{hash::rpo::RpoDigest, Felt, Word, ZERO};
-use miden_lib::transaction::TransactionKernel;
+use miden_crypto::{
+    dsa::rpo_falcon512::{PublicKey, SecretKey},
+    hash::rpo::RpoDigest,
+    Felt, Word, ZERO,
+};
 use miden_objects::{
     accounts::{Account, AccountId, StorageMap, StorageSlot},
-    transaction::TransactionScript,
+    transaction::ExecutedTransaction,
 };
-use miden_tx::{auth::TransactionAuthenticator, testing::MockChain, TransactionExecutor};
+use miden_processor::ExecutionError;
+use miden_tx::{auth::TransactionAuthenticator, TransactionExecutorError};
+use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
 
 use pm_accounts::{
-    oracle::{OracleAccountBuilder, ORACLE_COMPONENT_LIBRARY},
-    publisher::PublisherAccountBuilder,
-    utils::{new_pk_and_authenticator, word_to_masm},
+    oracle::{
+        admin_signers_advice, oracle_account_slot, oracle_storage_slots, pair_publisher_key,
+        publisher_index_key, set_admin_slots, set_pair_slots, ADMINS_CONFIG_SLOT, ADMINS_MAP_SLOT,
+        ERR_ORACLE_ADMINS_NOT_SORTED, ERR_ORACLE_ADMIN_ALREADY_REGISTERED,
+        ERR_ORACLE_ADMIN_THRESHOLD_TOO_HIGH, ERR_ORACLE_DEVIATION_TOO_HIGH, ERR_ORACLE_PAIR_PAUSED,
+        ERR_ORACLE_PAUSED, ERR_ORACLE_PUBLISHER_ALREADY_IN_PAIR, ERR_ORACLE_UNKNOWN_ADMIN,
+        ERR_ORACLE_UNKNOWN_PAIR, ERR_ORACLE_UNKNOWN_PUBLISHER, GLOBAL_PAUSE_KEY,
+        LAST_MEDIAN_MAP_SLOT, MAX_DEVIATION_SLOT, ORACLE_STORAGE_SIZE, OWNER_PUBLIC_KEY_SLOT,
+        PAIRS_MAP_SLOT, PAIR_PUBLISHERS_MAP_SLOT, PUBLISHERS_MAP_SLOT, PUBLISHER_COUNT_SLOT,
+        PUBLISHER_REGISTRY_SLOT, QUORUM_SLOT,
+    },
+    scripts::{
+        add_admin_script, add_pair_publisher_script, add_pair_script, compile_oracle_script,
+        expected_median_script, get_pair_script, median_script, oracle_get_entry_script,
+        pause_script, register_publisher_script, remove_admin_script, remove_pair_script,
+        set_admin_threshold_script, set_max_deviation_script, transfer_ownership_script,
+        unpause_script, ERR_MEDIAN_MISMATCH,
+    },
+};
+use pm_types::{median, Entry, PublisherId};
+
+use common::{
+    apply_script, assert_tx_error, authenticator, build_oracle, build_publisher,
+    build_publisher_with_entries, execute_script, mock_entry, mock_pair, new_key_and_authenticator,
+    random_entry, TestOracleBuilder, TestPublisherBuilder,
 };
 
-use common::{mock_entry, random_entry, FpiAdviceBuilder};
+/// Seed of the oracle owner key & account id.
+const ORACLE_SEED: [u8; 32] = [100; 32];
 
 #[test]
-fn test_oracle_get_entry() {
-    let entry = mock_entry();
-    let entry_as_word: Word = entry.try_into().unwrap();
-    let pair: Felt = entry_as_word[0];
-    let pair_word: Word = [pair, ZERO, ZERO, ZERO];
-
-    let (publisher_pub_key, _) = new_pk_and_authenticator([0_u8; 32]);
-    let publisher_id = 12345_u64;
-    let publisher_id_word = [Felt::new(publisher_id), ZERO, ZERO, ZERO];
-    let publisher_account_id = AccountId::try_from(publisher_id).unwrap();
-    let publisher_account = PublisherAccountBuilder::new(publisher_pub_key, publisher_account_id)
-        .with_storage_slots(vec![
-            // TODO: We need a leading empty map else indexing goes wrong.
-            StorageSlot::empty_map(),
-            // Entries map
-            StorageSlot::Map(
-                StorageMap::with_entries(vec![(
-                    // The key is the pair id
-                    RpoDigest::from(pair_word),
-                    // The value is the entry
-                    entry_as_word,
-                )])
+fn test_oracle_storage_layout() {
+    let (publishers, _) = generate_publishers_and_prices(2);
+    let publisher_ids = publisher_ids(&publishers);
+    let (admins, _) = new_admins_and_authenticator(2);
+    let pair = mock_pair_word();
+    let (oracle_account, _) = build_oracle(
+        TestOracleBuilder::new()
+            .with_storage_slots(oracle_storage_slots(&publisher_ids, 2).unwrap())
+            .with_pair(pair, 8, &publisher_ids)
+            .unwrap()
+            .with_admins(&admins, 1)
+            .unwrap()
+            .with_max_deviation(250)
+            .unwrap(),
+        ORACLE_SEED,
+    );
+    let storage = oracle_account.storage();
+    let value = |slot: u8| Word::from(storage.get_item(oracle_account_slot(slot)).unwrap());
+
+    // The oracle component is the only component of the account
+    assert_eq!(
+        storage.slots().len(),
+        oracle_account_slot(ORACLE_STORAGE_SIZE) as usize
+    );
+
+    let (owner_key, _) = new_key_and_authenticator(ORACLE_SEED);
+    assert_eq!(
+        value(OWNER_PUBLIC_KEY_SLOT),
+        Word::from(owner_key.public_key())
+    );
+    assert_eq!(value(QUORUM_SLOT), [Felt::new(2), ZERO, ZERO, ZERO]);
+    assert_eq!(
+        value(PUBLISHER_COUNT_SLOT),
+        [Felt::new(2), ZERO, ZERO, ZERO]
+    );
+    assert_eq!(
+        value(MAX_DEVIATION_SLOT),
+        [Felt::new(250), ZERO, ZERO, ZERO]
+    );
+    assert_eq!(
+        value(ADMINS_CONFIG_SLOT),
+        [Felt::new(1), Felt::new(2), ZERO, ZERO]
+    );
+
+    for (i, publisher_id) in publisher_ids.iter().enumerate() {
+        let publisher_id_word = PublisherId::from(*publisher_id).to_word();
+        assert_eq!(
+            storage
+                .get_map_item(
+                    oracle_account_slot(PUBLISHERS_MAP_SLOT),
+                    publisher_index_key(i as u32).into()
+                )
                 .unwrap(),
-            ),
-        ])
-        .build();
-
-    let (oracle_pub_key, oracle_auth) = new_pk_and_authenticator([1_u8; 32]);
-    let oracle_id = 98765_u64;
-    let oracle_account_id = AccountId::try_from(oracle_id).unwrap();
-    let oracle_account = OracleAccountBuilder::new(oracle_pub_key, oracle_account_id)
-        .with_storage_slots(vec![
-            // TODO: For some reasons, we have to add this map at index 0.
-            StorageSlot::empty_map(),
-            // Next publisher slot. Starts from idx 4 for our test since 3 is already populated.
-            StorageSlot::Value([Felt::new(4), ZERO, ZERO, ZERO]),
-            // Publisher registry
-            StorageSlot::Map(
-                StorageMap::with_entries(vec![(
-                    RpoDigest::new(publisher_id_word),
-                    [Felt::new(3), ZERO, ZERO, ZERO],
-                )])
+            publisher_id_word
+        );
+        assert_eq!(
+            storage
+                .get_map_item(
+                    oracle_account_slot(PUBLISHER_REGISTRY_SLOT),
+                    publisher_id_word
+                )
                 .unwrap(),
-            ),
-            StorageSlot::Value(publisher_id_word),
-        ])
-        .build();
+            [Felt::new(1), Felt::new(i as u64), ZERO, ZERO]
+        );
+        assert_eq!(
+            storage
+                .get_map_item(
+                    oracle_account_slot(PAIR_PUBLISHERS_MAP_SLOT),
+                    pair_publisher_key(pair, i as u32).into()
+                )
+                .unwrap(),
+            publisher_id_word
+        );
+    }
+    assert_eq!(
+        storage
+            .get_map_item(oracle_account_slot(PAIRS_MAP_SLOT), pair)
+            .unwrap(),
+        [Felt::new(1), Felt::new(8), Felt::new(2), ZERO]
+    );
+    for admin in admins {
+        assert_eq!(
+            storage
+                .get_map_item(oracle_account_slot(ADMINS_MAP_SLOT), admin.into())
+                .unwrap(),
+            [Felt::new(1), ZERO, ZERO, ZERO]
+        );
+    }
+}
 
-    let mut mock_chain =
-        MockChain::with_accounts(&[publisher_account.clone(), oracle_account.clone()]);
-    mock_chain.seal_block(None);
+#[test]
+fn test_oracle_builder_invalid_input() {
+    let (publishers, _) = generate_publishers_and_prices(2);
+    let publisher_ids = publisher_ids(&publishers);
+    let (admins, _) = new_admins_and_authenticator(2);
+
+    assert!(TestOracleBuilder::new()
+        .with_publishers(&[publisher_ids[0], publisher_ids[0]])
+        .is_err());
+    assert!(TestOracleBuilder::new().with_admins(&admins, 3).is_err());
+    assert!(TestOracleBuilder::new()
+        .with_admins(&[admins[0], admins[0]], 1)
+        .is_err());
+    assert!(TestOracleBuilder::new()
+        .with_storage_slots(vec![StorageSlot::empty_map()])
+        .with_pair(mock_pair_word(), 8, &publisher_ids)
+        .is_err());
+}
+
+#[test]
+fn test_oracle_get_entry() {
+    let entry = mock_entry();
+    let pair_word = entry.pair.to_word();
 
-    let tx_script_code = format!(
-        "
-        use.oracle_component::oracle_module
-        use.std::sys
+    let (publisher_account, _) = build_publisher_with_entries(&[entry], [1_u8; 32]);
+    let publisher_ids = [publisher_account.id()];
 
-        begin
-            push.{pair}
-            push.{publisher_id}
+    let (oracle_account, oracle_auth) = build_oracle(
+        TestOracleBuilder::new()
+            .with_publishers(&publisher_ids)
+            .unwrap()
+            .with_pair(pair_word, 8, &publisher_ids)
+            .unwrap(),
+        ORACLE_SEED,
+    );
 
-            call.oracle_module::get_entry
+    let tx_script_code =
+        oracle_get_entry_script(PublisherId::from(publisher_account.id()), pair_word);
+    let tx_script = compile_oracle_script(tx_script_code, vec![]).unwrap();
 
-            # push.{entry} assert_eqw
+    execute_script(
+        &oracle_account,
+        oracle_auth.clone(),
+        tx_script,
+        &[publisher_account],
+    )
+    .unwrap();
 
-            exec.sys::truncate_stack
-        end
-        ",
-        pair = word_to_masm(pair_word),
-        publisher_id = publisher_account.id(),
-        entry = word_to_masm(entry_as_word),
+    // The entries of unregistered publishers can't be read
+    let (unregistered_account, _) = build_publisher_with_entries(&[mock_entry()], [2_u8; 32]);
+    let tx_script_code =
+        oracle_get_entry_script(PublisherId::from(unregistered_account.id()), pair_word);
+    let tx_script = compile_oracle_script(tx_script_code, vec![]).unwrap();
+    assert_tx_error(
+        execute_script(
+            &oracle_account,
+            oracle_auth,
+            tx_script,
+            &[unregistered_account],
+        ),
+        ERR_ORACLE_UNKNOWN_PUBLISHER,
     );
+}
 
-    let tx_script = TransactionScript::compile(
-        tx_script_code,
-        [],
-        TransactionKernel::testing_assembler()
-            .with_library(ORACLE_COMPONENT_LIBRARY.as_ref())
-            .expect("adding oracle library should not fail")
-            .with_debug_mode(true)
-            .clone(),
+#[test]
+fn test_oracle_register_publisher() {
+    let (mut oracle_account, oracle_auth) = build_oracle(TestOracleBuilder::new(), ORACLE_SEED);
+
+    let (publisher_account, _) = build_publisher(TestPublisherBuilder::new(), [1_u8; 32]);
+    let publisher_id = PublisherId::from(publisher_account.id());
+    let publisher_id_word = publisher_id.to_word();
+
+    let executed_transaction = execute_oracle_script(
+        &oracle_account,
+        oracle_auth.clone(),
+        register_publisher_script(publisher_id),
+        vec![],
     )
     .unwrap();
+    oracle_account
+        .apply_delta(executed_transaction.account_delta())
+        .unwrap();
+
+    assert_eq!(
+        oracle_account
+            .storage()
+            .get_map_item(
+                oracle_account_slot(PUBLISHER_REGISTRY_SLOT),
+                publisher_id_word
+            )
+            .unwrap(),
+        [Felt::new(1), ZERO, ZERO, ZERO]
+    );
+    assert_eq!(
+        oracle_account
+            .storage()
+            .get_map_item(
+                oracle_account_slot(PUBLISHERS_MAP_SLOT),
+                publisher_index_key(0).into()
+            )
+            .unwrap(),
+        publisher_id_word
+    );
+    assert_eq!(
+        oracle_account
+            .storage()
+            .get_item(oracle_account_slot(PUBLISHER_COUNT_SLOT))
+            .unwrap(),
+        RpoDigest::new([Felt::new(1), ZERO, ZERO, ZERO])
+    );
+
+    // A publisher can't be registered twice
+    let tx_script_code = register_publisher_script(publisher_id);
+    assert!(execute_oracle_script(&oracle_account, oracle_auth, tx_script_code, vec![]).is_err());
+}
 
-    let advice_inputs = FpiAdviceBuilder::new(&mock_chain)
-        .with_account(&publisher_account)
-        .build();
-
-    let tx_context = mock_chain
-        .build_tx_context(oracle_account.id(), &[], &[])
-        .advice_inputs(advice_inputs)
-        .tx_script(tx_script)
-        .build();
-
-    let mut executor =
-        TransactionExecutor::new(Arc::new(tx_context.clone()), Some(oracle_auth.clone()))
-            .with_debug_mode(true)
-            .with_tracing();
-
-    // load the foreign account's code into the transaction executor
-    executor.load_account_code(publisher_account.code());
-
-    // Show the next publisher slot
-    println!(
-        "==== ORACLE ====\n0: {:?}\n1: {:?}\n2: {:?}",
-        // TODO: Item (0) is populated with something else? We expected a map?
-        oracle_account.storage().get_item(0),
-        // TODO: Item (1) is populated with something else?
-        oracle_account.storage().get_item(1),
-        // TODO: We have to use "2" even though it's supposed to be index 1.
-        oracle_account.storage().get_item(2),
-    );
-
-    // Show the registered publisher
-    println!(
-        "{:?}",
-        // TODO: We have to use "3" even though it's supposed to be index 2.
-        oracle_account.storage().get_map_item(3, publisher_id_word)
-    );
-
-    // Show the expected entry
-    println!(
-        "==== PUBLISHER ====\n0: {:?}\n1: {:?}\nPublisher Pair slot: {:?}",
-        publisher_account.storage().get_item(0),
-        // TODO: This looks to be the leading empty map.. but why "1"?
-        publisher_account.storage().get_map_item(1, pair_word),
-        // TODO: We have to use "2" even though it's supposed to be index 1?
-        publisher_account.storage().get_map_item(2, pair_word)
-    );
-
-    // execute the tx. The test assertion is made in the masm script.
-    let _ = executor
-        .execute_transaction(
-            oracle_account.id(),
-            tx_context.tx_inputs().block_header().block_num(),
-            &[],
-            tx_context.tx_args().clone(),
+#[test]
+fn test_oracle_get_median() {
+    let (publishers, prices) = generate_publishers_and_prices(4);
+    let (oracle_account, oracle_auth) = generate_oracle_account(&publishers);
+
+    execute_get_median(
+        &publishers,
+        &oracle_account,
+        oracle_auth,
+        median(&prices).unwrap(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_oracle_get_median_single_entry() {
+    // The price must be read from the entry, not its decimals or timestamp
+    let price = mock_entry().price;
+    let publishers = generate_publishers(&[price]);
+    let (oracle_account, oracle_auth) = generate_oracle_account(&publishers);
+
+    execute_get_median(&publishers, &oracle_account, oracle_auth, price).unwrap();
+}
+
+#[test]
+fn test_oracle_get_median_two_entries() {
+    // Both prices overflow a u32, their average must be computed on 64 bits
+    let prices = [110_000_00000000, 90_000_00000001];
+    let publishers = generate_publishers(&prices);
+    let (oracle_account, oracle_auth) = generate_oracle_account(&publishers);
+
+    execute_get_median(&publishers, &oracle_account, oracle_auth, 100_000_00000000).unwrap();
+}
+
+#[test]
+fn test_oracle_get_median_mismatch() {
+    let (publishers, prices) = generate_publishers_and_prices(3);
+    let (oracle_account, oracle_auth) = generate_oracle_account(&publishers);
+
+    let result = execute_get_median(
+        &publishers,
+        &oracle_account,
+        oracle_auth,
+        median(&prices).unwrap() + 1,
+    );
+    assert_tx_error(result, ERR_MEDIAN_MISMATCH);
+}
+
+#[test]
+fn test_oracle_get_median_quorum_not_reached() {
+    let (publishers, prices) = generate_publishers_and_prices(3);
+    let publisher_ids = publisher_ids(&publishers);
+    let (oracle_account, oracle_auth) = build_oracle(
+        TestOracleBuilder::new()
+            .with_storage_slots(oracle_storage_slots(&publisher_ids, 4).unwrap())
+            .with_pair(mock_pair_word(), 8, &publisher_ids)
+            .unwrap(),
+        ORACLE_SEED,
+    );
+
+    let result = execute_get_median(
+        &publishers,
+        &oracle_account,
+        oracle_auth,
+        median(&prices).unwrap(),
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_oracle_get_median_checked() {
+    let (publishers, prices) = generate_publishers_and_prices(3);
+    let expected_median = median(&prices).unwrap();
+    let publisher_ids = publisher_ids(&publishers);
+    let pair = mock_pair_word();
+
+    // 1% max deviation, administrated by the owner unless admins are provided
+    let oracle_with_last_median = |last_median: Option<u64>, admins: &[PublicKey]| {
+        let mut slots = oracle_storage_slots(&publisher_ids, 1).unwrap();
+        set_pair_slots(&mut slots, pair, 8, &publisher_ids).unwrap();
+        set_admin_slots(&mut slots, admins, admins.len().min(1) as u32).unwrap();
+        slots[MAX_DEVIATION_SLOT as usize] = StorageSlot::Value([Felt::new(100), ZERO, ZERO, ZERO]);
+        if let Some(last_median) = last_median {
+            slots[LAST_MEDIAN_MAP_SLOT as usize] = StorageSlot::Map(
+                StorageMap::with_entries([(
+                    RpoDigest::new(pair),
+                    [Felt::new(last_median), ZERO, ZERO, ZERO],
+                )])
+                .unwrap(),
+            );
+        }
+        build_oracle(
+            TestOracleBuilder::new().with_storage_slots(slots),
+            ORACLE_SEED,
+        )
+    };
+    let stored_last_median = |oracle_account: &Account| {
+        oracle_account
+            .storage()
+            .get_map_item(oracle_account_slot(LAST_MEDIAN_MAP_SLOT), pair)
+            .unwrap()
+    };
+
+    // The first median of a pair is always accepted, then the median can move by less than
+    // the max deviation
+    for last_median in [None, Some(expected_median + expected_median / 200)] {
+        let (mut oracle_account, oracle_auth) = oracle_with_last_median(last_median, &[]);
+        let executed_transaction = execute_checked_median(
+            &publishers,
+            &oracle_account,
+            oracle_auth,
+            vec![],
+            expected_median,
         )
         .unwrap();
+        oracle_account
+            .apply_delta(executed_transaction.account_delta())
+            .unwrap();
+        assert_eq!(
+            stored_last_median(&oracle_account),
+            [Felt::new(expected_median), ZERO, ZERO, ZERO]
+        );
+    }
+
+    let (oracle_account, oracle_auth) = oracle_with_last_median(Some(expected_median * 2), &[]);
+    let result = execute_checked_median(
+        &publishers,
+        &oracle_account,
+        oracle_auth,
+        vec![],
+        expected_median,
+    );
+    assert_tx_error(result, ERR_ORACLE_DEVIATION_TOO_HIGH);
+
+    // Only the oracle administration can move the last accepted median
+    let (admins, admins_auth) = new_admins_and_authenticator(2);
+    let (oracle_account, _) = oracle_with_last_median(None, &admins[..1]);
+    let (signers_key, signers) = admin_signers_advice(&admins[1..]);
+    let result = execute_checked_median(
+        &publishers,
+        &oracle_account,
+        admins_auth,
+        vec![(signers_key.into(), signers)],
+        expected_median,
+    );
+    assert_tx_error(result, ERR_ORACLE_UNKNOWN_ADMIN);
 }
 
 #[test]
-fn test_oracle_register_publisher() {
-    let (oracle_pub_key, oracle_auth) = new_pk_and_authenticator([1_u8; 32]);
-    let oracle_id = 98765_u64;
-    let oracle_account_id = AccountId::try_from(oracle_id).unwrap();
-    let mut oracle_account = OracleAccountBuilder::new(oracle_pub_key, oracle_account_id).build();
+fn test_oracle_median_script() {
+    let (publishers, prices) = generate_publishers_and_prices(3);
+    let (mut oracle_account, oracle_auth) = generate_oracle_account(&publishers);
+    let pair = mock_pair_word();
+
+    execute_oracle_fpi_script(
+        &publishers,
+        &oracle_account,
+        oracle_auth.clone(),
+        median_script(pair, false),
+    )
+    .unwrap();
 
-    let mut mock_chain = MockChain::with_accounts(&[oracle_account.clone()]);
-    mock_chain.seal_block(None);
+    let executed_transaction = execute_oracle_fpi_script(
+        &publishers,
+        &oracle_account,
+        oracle_auth,
+        median_script(pair, true),
+    )
+    .unwrap();
+    oracle_account
+        .apply_delta(executed_transaction.account_delta())
+        .unwrap();
+    assert_eq!(
+        oracle_account
+            .storage()
+            .get_map_item(oracle_account_slot(LAST_MEDIAN_MAP_SLOT), pair)
+            .unwrap(),
+        [Felt::new(median(&prices).unwrap()), ZERO, ZERO, ZERO]
+    );
+}
 
-    let publisher_id = 12345_u64;
-    let publisher_id_word = [ZERO, ZERO, ZERO, Felt::new(publisher_id)];
-    let publisher_account_id = AccountId::try_from(publisher_id).unwrap();
+#[test]
+fn test_oracle_pairs() {
+    let (publishers, prices) = generate_publishers_and_prices(3);
+    let publisher_ids = publisher_ids(&publishers);
+    let (mut oracle_account, oracle_auth) = build_oracle(
+        TestOracleBuilder::new()
+            .with_publishers(&publisher_ids)
+            .unwrap(),
+        ORACLE_SEED,
+    );
+    let pair = mock_pair_word();
+
+    // Unknown pairs fail right away
+    let result = execute_get_median(
+        &publishers,
+        &oracle_account,
+        oracle_auth.clone(),
+        median(&prices).unwrap(),
+    );
+    assert_tx_error(result, ERR_ORACLE_UNKNOWN_PAIR);
+
+    let mut tx_scripts = vec![add_pair_script(pair, 8)];
+    // Only the first two publishers are allowed to publish the pair
+    for publisher_id in publisher_ids.iter().take(2) {
+        tx_scripts.push(add_pair_publisher_script(
+            pair,
+            PublisherId::from(*publisher_id),
+        ));
+    }
+    tx_scripts.push(get_pair_script(pair));
+    for tx_script_code in tx_scripts {
+        let executed_transaction =
+            execute_oracle_script(&oracle_account, oracle_auth.clone(), tx_script_code, vec![])
+                .unwrap();
+        oracle_account
+            .apply_delta(executed_transaction.account_delta())
+            .unwrap();
+    }
 
-    let tx_script_code = format!(
-        "
-        use.oracle_component::oracle_module
-        use.std::sys
+    assert_eq!(
+        oracle_account
+            .storage()
+            .get_map_item(oracle_account_slot(PAIRS_MAP_SLOT), pair)
+            .unwrap(),
+        [Felt::new(1), Felt::new(8), Felt::new(2), ZERO]
+    );
 
-        begin
-            push.{publisher_account_id}
-            call.oracle_module::register_publisher
-            exec.sys::truncate_stack
-        end
-        ",
+    // A publisher can't be added twice to the same pair
+    let result = execute_oracle_script(
+        &oracle_account,
+        oracle_auth.clone(),
+        add_pair_publisher_script(pair, PublisherId::from(publisher_ids[0])),
+        vec![],
     );
+    assert_tx_error(result, ERR_ORACLE_PUBLISHER_ALREADY_IN_PAIR);
 
-    let tx_script = TransactionScript::compile(
-        tx_script_code,
-        [],
-        TransactionKernel::testing_assembler()
-            .with_library(ORACLE_COMPONENT_LIBRARY.as_ref())
-            .expect("adding oracle library should not fail")
-            .with_debug_mode(true)
-            .clone(),
+    // The median is only computed with the entries of the pair publishers
+    execute_get_median(
+        &publishers,
+        &oracle_account,
+        oracle_auth,
+        median(&prices[..2]).unwrap(),
     )
     .unwrap();
+}
 
-    let tx_context = mock_chain
-        .build_tx_context(oracle_account.id(), &[], &[])
-        .tx_script(tx_script)
-        .build();
-
-    let executor =
-        TransactionExecutor::new(Arc::new(tx_context.clone()), Some(oracle_auth.clone()))
-            .with_debug_mode(true)
-            .with_tracing();
-
-    // execute the tx. The test assertion is made in the masm script.
-    let executed_transaction = executor
-        .execute_transaction(
-            oracle_account.id(),
-            tx_context.tx_inputs().block_header().block_num(),
-            &[],
-            tx_context.tx_args().clone(),
-        )
-        .unwrap();
+#[test]
+fn test_oracle_remove_pair() {
+    let (publishers, prices) = generate_publishers_and_prices(3);
+    let (mut oracle_account, oracle_auth) = generate_oracle_account(&publishers);
+    let pair = mock_pair_word();
+
+    apply_oracle_script(
+        &mut oracle_account,
+        oracle_auth.clone(),
+        remove_pair_script(pair),
+        vec![],
+    );
+    assert_eq!(
+        oracle_account
+            .storage()
+            .get_map_item(oracle_account_slot(PAIRS_MAP_SLOT), pair)
+            .unwrap(),
+        [ZERO; 4]
+    );
 
+    let result = execute_get_median(
+        &publishers,
+        &oracle_account,
+        oracle_auth.clone(),
+        median(&prices).unwrap(),
+    );
+    assert_tx_error(result, ERR_ORACLE_UNKNOWN_PAIR);
+
+    let result = execute_oracle_script(
+        &oracle_account,
+        oracle_auth,
+        remove_pair_script(pair),
+        vec![],
+    );
+    assert_tx_error(result, ERR_ORACLE_UNKNOWN_PAIR);
+}
+
+#[test]
+fn test_oracle_set_max_deviation() {
+    let (mut oracle_account, oracle_auth) = build_oracle(TestOracleBuilder::new(), ORACLE_SEED);
+
+    apply_oracle_script(
+        &mut oracle_account,
+        oracle_auth,
+        set_max_deviation_script(250),
+        vec![],
+    );
+    assert_eq!(
+        oracle_account
+            .storage()
+            .get_item(oracle_account_slot(MAX_DEVIATION_SLOT))
+            .unwrap(),
+        RpoDigest::new([Felt::new(250), ZERO, ZERO, ZERO])
+    );
+}
+
+#[test]
+fn test_oracle_transfer_ownership() {
+    let (mut oracle_account, oracle_auth) = build_oracle(TestOracleBuilder::new(), ORACLE_SEED);
+
+    let (new_owner_key, _) = new_key_and_authenticator([2_u8; 32]);
+    let new_owner = new_owner_key.public_key();
+    let tx_script_code = transfer_ownership_script(new_owner);
+
+    let executed_transaction =
+        execute_oracle_script(&oracle_account, oracle_auth, tx_script_code, vec![]).unwrap();
     oracle_account
         .apply_delta(executed_transaction.account_delta())
         .unwrap();
@@ -225,195 +573,350 @@ fn test_oracle_register_publisher() {
     assert_eq!(
         oracle_account
             .storage()
-            .get_map_item(3, publisher_id_word)
+            .get_item(oracle_account_slot(OWNER_PUBLIC_KEY_SLOT))
             .unwrap(),
-        [Felt::new(3), ZERO, ZERO, ZERO]
+        RpoDigest::new(new_owner.into())
+    );
+}
+
+#[test]
+fn test_oracle_admins_threshold() {
+    let (admins, admins_auth) = new_admins_and_authenticator(3);
+    let (oracle_account, _) = build_oracle(
+        TestOracleBuilder::new().with_admins(&admins, 2).unwrap(),
+        ORACLE_SEED,
+    );
+
+    let (publisher_account, _) = build_publisher(TestPublisherBuilder::new(), [1_u8; 32]);
+    let tx_script_code = register_publisher_script(PublisherId::from(publisher_account.id()));
+
+    let (signers_key, signers) = admin_signers_advice(&admins[..2]);
+    execute_oracle_script(
+        &oracle_account,
+        admins_auth.clone(),
+        tx_script_code.clone(),
+        vec![(signers_key.into(), signers.clone())],
+    )
+    .unwrap();
+
+    // A single admin can't reach the threshold, the second signer key is missing
+    let (signers_key, single_signer) = admin_signers_advice(&admins[..1]);
+    let result = execute_oracle_script(
+        &oracle_account,
+        admins_auth.clone(),
+        tx_script_code.clone(),
+        vec![(signers_key.into(), single_signer)],
+    );
+    assert!(matches!(
+        result,
+        Err(TransactionExecutorError::TransactionProgramExecutionFailed(
+            ExecutionError::AdviceStackReadFailed(_)
+        ))
+    ));
+
+    // Keys that aren't registered as admins can't sign, even when the authenticator holds them
+    let (outsider_key, _) = new_key_and_authenticator([2_u8; 32]);
+    let outsider_auth = authenticator(&[outsider_key.clone()]);
+    let (signers_key, outsiders) = admin_signers_advice(&[outsider_key.public_key()]);
+    let result = execute_oracle_script(
+        &oracle_account,
+        outsider_auth,
+        tx_script_code.clone(),
+        vec![(signers_key.into(), outsiders)],
+    );
+    assert_tx_error(result, ERR_ORACLE_UNKNOWN_ADMIN);
+
+    // The signers must be sorted so the same admin can't sign twice
+    let unsorted_signers: Vec<Felt> = signers.chunks(4).rev().flatten().copied().collect();
+    let result = execute_oracle_script(
+        &oracle_account,
+        admins_auth,
+        tx_script_code,
+        vec![(signers_key.into(), unsorted_signers)],
+    );
+    assert_tx_error(result, ERR_ORACLE_ADMINS_NOT_SORTED);
+}
+
+#[test]
+fn test_oracle_add_admin() {
+    let (mut oracle_account, oracle_auth) = build_oracle(TestOracleBuilder::new(), ORACLE_SEED);
+    let (admins, _) = new_admins_and_authenticator(1);
+
+    apply_oracle_script(
+        &mut oracle_account,
+        oracle_auth.clone(),
+        add_admin_script(admins[0]),
+        vec![],
     );
     assert_eq!(
-        oracle_account.storage().get_item(4).unwrap(),
-        RpoDigest::new(publisher_id_word)
+        oracle_account
+            .storage()
+            .get_map_item(oracle_account_slot(ADMINS_MAP_SLOT), admins[0].into())
+            .unwrap(),
+        [Felt::new(1), ZERO, ZERO, ZERO]
     );
     assert_eq!(
-        oracle_account.storage().get_item(2).unwrap(),
-        RpoDigest::new([Felt::new(4), ZERO, ZERO, ZERO])
+        oracle_account
+            .storage()
+            .get_item(oracle_account_slot(ADMINS_CONFIG_SLOT))
+            .unwrap(),
+        RpoDigest::new([ZERO, Felt::new(1), ZERO, ZERO])
     );
+
+    let result = execute_oracle_script(
+        &oracle_account,
+        oracle_auth,
+        add_admin_script(admins[0]),
+        vec![],
+    );
+    assert_tx_error(result, ERR_ORACLE_ADMIN_ALREADY_REGISTERED);
 }
 
 #[test]
-fn test_oracle_get_median() {
-    let (publishers, expected_median) = generate_publishers_and_median(4);
-    let (oracle_account, oracle_auth) = generate_oracle_account(&publishers);
-    let mut mock_chain = setup_mock_chain(&publishers, &oracle_account);
-    mock_chain.seal_block(None);
+fn test_oracle_set_admin_threshold() {
+    let (admins, _) = new_admins_and_authenticator(2);
+    let (mut oracle_account, oracle_auth) = build_oracle(
+        TestOracleBuilder::new().with_admins(&admins, 0).unwrap(),
+        ORACLE_SEED,
+    );
 
-    let tx_script_code = format!(
-        "
-        use.oracle_component::oracle_module
-        use.std::sys
+    // The threshold can't be greater than the number of admins
+    let result = execute_oracle_script(
+        &oracle_account,
+        oracle_auth.clone(),
+        set_admin_threshold_script(3),
+        vec![],
+    );
+    assert_tx_error(result, ERR_ORACLE_ADMIN_THRESHOLD_TOO_HIGH);
 
-        begin
-            push.{pair}
+    apply_oracle_script(
+        &mut oracle_account,
+        oracle_auth,
+        set_admin_threshold_script(2),
+        vec![],
+    );
+    assert_eq!(
+        oracle_account
+            .storage()
+            .get_item(oracle_account_slot(ADMINS_CONFIG_SLOT))
+            .unwrap(),
+        RpoDigest::new([Felt::new(2), Felt::new(2), ZERO, ZERO])
+    );
+}
 
-            call.oracle_module::get_median
+#[test]
+fn test_oracle_remove_admin() {
+    let (admins, admins_auth) = new_admins_and_authenticator(3);
+    let (mut oracle_account, _) = build_oracle(
+        TestOracleBuilder::new().with_admins(&admins, 2).unwrap(),
+        ORACLE_SEED,
+    );
+    let (signers_key, signers) = admin_signers_advice(&admins[..2]);
 
-            push.{expected_median} assert_eq
+    apply_oracle_script(
+        &mut oracle_account,
+        admins_auth.clone(),
+        remove_admin_script(admins[2]),
+        vec![(signers_key.into(), signers.clone())],
+    );
+    assert_eq!(
+        oracle_account
+            .storage()
+            .get_map_item(oracle_account_slot(ADMINS_MAP_SLOT), admins[2].into())
+            .unwrap(),
+        [ZERO; 4]
+    );
+    assert_eq!(
+        oracle_account
+            .storage()
+            .get_item(oracle_account_slot(ADMINS_CONFIG_SLOT))
+            .unwrap(),
+        RpoDigest::new([Felt::new(2), Felt::new(2), ZERO, ZERO])
+    );
 
-            exec.sys::truncate_stack
-        end
-        ",
-        pair = word_to_masm([mock_entry().pair.try_into().unwrap(), ZERO, ZERO, ZERO]),
-        expected_median = expected_median
+    // A removed admin can't sign anymore
+    let (signers_key, with_removed_admin) = admin_signers_advice(&[admins[0], admins[2]]);
+    let result = execute_oracle_script(
+        &oracle_account,
+        admins_auth.clone(),
+        set_admin_threshold_script(1),
+        vec![(signers_key.into(), with_removed_admin)],
     );
+    assert_tx_error(result, ERR_ORACLE_UNKNOWN_ADMIN);
+
+    // The remaining admins must still be able to reach the threshold
+    let result = execute_oracle_script(
+        &oracle_account,
+        admins_auth,
+        remove_admin_script(admins[1]),
+        vec![(signers_key.into(), signers)],
+    );
+    assert_tx_error(result, ERR_ORACLE_ADMIN_THRESHOLD_TOO_HIGH);
+}
 
-    let tx_script = TransactionScript::compile(
-        tx_script_code,
-        [],
-        TransactionKernel::testing_assembler()
-            .with_library(ORACLE_COMPONENT_LIBRARY.as_ref())
-            .expect("adding oracle library should not fail")
-            .with_debug_mode(true)
-            .clone(),
+#[test]
+fn test_oracle_pause() {
+    let (publishers, prices) = generate_publishers_and_prices(3);
+    let expected_median = median(&prices).unwrap();
+    let (mut oracle_account, oracle_auth) = generate_oracle_account(&publishers);
+    let pair = mock_pair_word();
+
+    // Pausing the pair makes the median unavailable
+    let executed_transaction = execute_oracle_script(
+        &oracle_account,
+        oracle_auth.clone(),
+        pause_script(pair),
+        vec![],
     )
     .unwrap();
+    oracle_account
+        .apply_delta(executed_transaction.account_delta())
+        .unwrap();
+    let result = execute_get_median(
+        &publishers,
+        &oracle_account,
+        oracle_auth.clone(),
+        expected_median,
+    );
+    assert_tx_error(result, ERR_ORACLE_PAIR_PAUSED);
 
-    let mut advice_inputs_builder = FpiAdviceBuilder::new(&mock_chain);
-    for (_, publisher_account) in publishers.iter() {
-        advice_inputs_builder.with_account(publisher_account);
-    }
-    let advice_inputs = advice_inputs_builder.build();
-
-    let tx_context = mock_chain
-        .build_tx_context(oracle_account.id(), &[], &[])
-        .advice_inputs(advice_inputs)
-        .tx_script(tx_script)
-        .build();
-
-    let mut executor =
-        TransactionExecutor::new(Arc::new(tx_context.clone()), Some(oracle_auth.clone()))
-            .with_debug_mode(true)
-            .with_tracing();
-
-    // load the foreign account's code into the transaction executor
-    for (_, publisher) in publishers.iter() {
-        executor.load_account_code(publisher.code());
+    // The global pause stays active after the pair is unpaused
+    for tx_script_code in [pause_script(GLOBAL_PAUSE_KEY), unpause_script(pair)] {
+        let executed_transaction =
+            execute_oracle_script(&oracle_account, oracle_auth.clone(), tx_script_code, vec![])
+                .unwrap();
+        oracle_account
+            .apply_delta(executed_transaction.account_delta())
+            .unwrap();
     }
+    let result = execute_get_median(
+        &publishers,
+        &oracle_account,
+        oracle_auth.clone(),
+        expected_median,
+    );
+    assert_tx_error(result, ERR_ORACLE_PAUSED);
 
-    // execute the tx. The test assertion is made in the masm script.
-    let _ = executor
-        .execute_transaction(
-            oracle_account.id(),
-            tx_context.tx_inputs().block_header().block_num(),
-            &[],
-            tx_context.tx_args().clone(),
-        )
+    let executed_transaction = execute_oracle_script(
+        &oracle_account,
+        oracle_auth.clone(),
+        unpause_script(GLOBAL_PAUSE_KEY),
+        vec![],
+    )
+    .unwrap();
+    oracle_account
+        .apply_delta(executed_transaction.account_delta())
         .unwrap();
+    execute_get_median(&publishers, &oracle_account, oracle_auth, expected_median).unwrap();
 }
 
 // ================ UTILITIES ================
 
-pub fn generate_publishers_and_median(n: usize) -> (Vec<(Word, Account)>, u64) {
-    let mut generated_publishers = Vec::with_capacity(n);
-    let mut prices = Vec::with_capacity(n);
-
-    for publisher_id in 1..=n as u64 {
-        let entry = random_entry();
-        // Store the price for median calculation
-        prices.push(entry.price);
-
-        let entry_as_word: Word = entry.try_into().unwrap();
-        let pair: Felt = entry_as_word[0];
-        let pair_word: Word = [pair, ZERO, ZERO, ZERO];
-
-        let (publisher_pub_key, _) = new_pk_and_authenticator([0_u8; 32]);
-        let publisher_account_id = AccountId::try_from(publisher_id * 10000).unwrap();
-
-        let publisher_account =
-            PublisherAccountBuilder::new(publisher_pub_key, publisher_account_id)
-                .with_storage_slots(vec![
-                    StorageSlot::empty_map(),
-                    StorageSlot::Map(
-                        StorageMap::with_entries(vec![(RpoDigest::from(pair_word), entry_as_word)])
-                            .unwrap(),
-                    ),
-                ])
-                .build();
-
-        generated_publishers.push((pair_word, publisher_account));
-    }
+/// Builds `n` publishers with an entry of the mock pair each, returns them with their prices.
+pub fn generate_publishers_and_prices(n: u8) -> (Vec<Account>, Vec<u64>) {
+    let mut rng = ChaCha20Rng::from_seed([42_u8; 32]);
+    let prices: Vec<u64> = (0..n).map(|_| random_entry(&mut rng).price).collect();
+    (generate_publishers(&prices), prices)
+}
 
-    // Calculate median
-    prices.sort_unstable();
-    let median = if prices.len() % 2 == 0 {
-        (prices[prices.len() / 2 - 1] + prices[prices.len() / 2]) / 2
-    } else {
-        prices[prices.len() / 2]
-    };
+/// Builds a publisher per price, each with an entry of the mock pair at that price.
+pub fn generate_publishers(prices: &[u64]) -> Vec<Account> {
+    prices
+        .iter()
+        .zip(1_u8..)
+        .map(|(price, i)| {
+            let entry = Entry {
+                price: *price,
+                ..mock_entry()
+            };
+            build_publisher_with_entries(&[entry], [i; 32]).0
+        })
+        .collect()
+}
 
-    (generated_publishers, median)
+pub fn publisher_ids(publishers: &[Account]) -> Vec<AccountId> {
+    publishers.iter().map(|publisher| publisher.id()).collect()
 }
 
+/// Oracle with the publishers registered & allowed to publish the mock pair.
 pub fn generate_oracle_account(
-    publisher_setups: &[(Word, Account)],
+    publishers: &[Account],
 ) -> (Account, Arc<dyn TransactionAuthenticator>) {
-    let (oracle_pub_key, oracle_auth) = new_pk_and_authenticator([1_u8; 32]);
-    let oracle_id = 98765_u64;
-    let oracle_account_id = AccountId::try_from(oracle_id).unwrap();
-
-    // Start building the storage slots
-    let mut storage_slots = Vec::new();
-
-    // 1. Add empty map at index 0
-    storage_slots.push(StorageSlot::empty_map());
-
-    // 2. Next publisher slot (number of publishers + 4)
-    let next_publisher_slot = publisher_setups.len() as u64 + 4;
-    storage_slots.push(StorageSlot::Value([
-        Felt::new(next_publisher_slot),
-        ZERO,
-        ZERO,
-        ZERO,
-    ]));
-
-    // 3. Build publisher registry map
-    let mut registry_entries = Vec::new();
-    for (i, (_, publisher_account)) in publisher_setups.iter().enumerate() {
-        let publisher_id_word = [publisher_account.id().into(), ZERO, ZERO, ZERO];
-        let slot_index = (i as u64) + 4; // Start from slot 4
-
-        registry_entries.push((
-            RpoDigest::new(publisher_id_word),
-            [Felt::new(slot_index), ZERO, ZERO, ZERO],
-        ));
-    }
+    let publisher_ids = publisher_ids(publishers);
+    build_oracle(
+        TestOracleBuilder::new()
+            .with_publishers(&publisher_ids)
+            .unwrap()
+            .with_pair(mock_pair_word(), 8, &publisher_ids)
+            .unwrap(),
+        ORACLE_SEED,
+    )
+}
 
-    storage_slots.push(StorageSlot::Map(
-        StorageMap::with_entries(registry_entries).unwrap(),
-    ));
+/// Word of the mock pair, as pushed to the oracle procedures.
+pub fn mock_pair_word() -> Word {
+    mock_pair().to_word()
+}
 
-    // 4. Add publisher ID values sequentially
-    for (_, publisher_account) in publisher_setups.iter() {
-        storage_slots.push(StorageSlot::Value([
-            publisher_account.id().into(),
-            ZERO,
-            ZERO,
-            ZERO,
-        ]));
-    }
+pub fn execute_get_median(
+    publishers: &[Account],
+    oracle_account: &Account,
+    oracle_auth: Arc<dyn TransactionAuthenticator>,
+    expected_median: u64,
+) -> Result<ExecutedTransaction, TransactionExecutorError> {
+    let tx_script_code = expected_median_script(mock_pair_word(), false, expected_median);
+    execute_oracle_fpi_script(publishers, oracle_account, oracle_auth, tx_script_code)
+}
 
-    (
-        OracleAccountBuilder::new(oracle_pub_key, oracle_account_id)
-            .with_storage_slots(storage_slots)
-            .build(),
-        oracle_auth,
-    )
+/// Executes `get_median_checked`, with the admin signers in `advice` once a threshold is set.
+pub fn execute_checked_median(
+    publishers: &[Account],
+    oracle_account: &Account,
+    oracle_auth: Arc<dyn TransactionAuthenticator>,
+    advice: Vec<(Word, Vec<Felt>)>,
+    expected_median: u64,
+) -> Result<ExecutedTransaction, TransactionExecutorError> {
+    let tx_script_code = expected_median_script(mock_pair_word(), true, expected_median);
+    let tx_script = compile_oracle_script(tx_script_code, advice).unwrap();
+    execute_script(oracle_account, oracle_auth, tx_script, publishers)
 }
 
-pub fn setup_mock_chain(publishers: &[(Word, Account)], oracle_account: &Account) -> MockChain {
-    let mut accounts: Vec<Account> = publishers
-        .iter()
-        .cloned()
-        .map(|(_, publisher)| publisher)
+/// Executes a script on the oracle, with the publishers as foreign accounts.
+pub fn execute_oracle_fpi_script(
+    publishers: &[Account],
+    oracle_account: &Account,
+    oracle_auth: Arc<dyn TransactionAuthenticator>,
+    tx_script_code: String,
+) -> Result<ExecutedTransaction, TransactionExecutorError> {
+    let tx_script = compile_oracle_script(tx_script_code, vec![]).unwrap();
+    execute_script(oracle_account, oracle_auth, tx_script, publishers)
+}
+
+pub fn new_admins_and_authenticator(n: u8) -> (Vec<PublicKey>, Arc<dyn TransactionAuthenticator>) {
+    let secret_keys: Vec<SecretKey> = (0..n)
+        .map(|i| SecretKey::with_rng(&mut ChaCha20Rng::from_seed([i + 10; 32])))
         .collect();
-    accounts.push(oracle_account.clone());
-    MockChain::with_accounts(&accounts)
+    let public_keys = secret_keys.iter().map(|sk| sk.public_key()).collect();
+    (public_keys, authenticator(&secret_keys))
+}
+
+/// Executes a script on the oracle and applies its changes.
+pub fn apply_oracle_script(
+    oracle_account: &mut Account,
+    oracle_auth: Arc<dyn TransactionAuthenticator>,
+    tx_script_code: String,
+    advice: Vec<(Word, Vec<Felt>)>,
+) -> ExecutedTransaction {
+    let tx_script = compile_oracle_script(tx_script_code, advice).unwrap();
+    apply_script(oracle_account, oracle_auth, tx_script, &[])
+}
+
+pub fn execute_oracle_script(
+    oracle_account: &Account,
+    oracle_auth: Arc<dyn TransactionAuthenticator>,
+    tx_script_code: String,
+    advice: Vec<(Word, Vec<Felt>)>,
+) -> Result<ExecutedTransaction, TransactionExecutorError> {
+    let tx_script = compile_oracle_script(tx_script_code, advice).unwrap();
+    execute_script(oracle_account, oracle_auth, tx_script, &[])
 }
@@ -0,0 +1,193 @@
+mod common;
+
+use std::{str::FromStr, sync::Arc};
+
+use miden_crypto::{hash::rpo::Rpo256, Felt, Word};
+use miden_objects::{accounts::Account, transaction::ExecutedTransaction};
+use miden_tx::{auth::TransactionAuthenticator, TransactionExecutorError};
+
+use pm_accounts::{
+    publisher::{entries_advice, entry_to_words, publisher_account_slot, ENTRIES_MAP_SLOT},
+    scripts::{
+        compile_publisher_script, publish_entries_script, publish_entry_script,
+        publisher_get_entry_script,
+    },
+    utils::word_to_masm,
+};
+use pm_types::{Entry, Pair};
+
+use common::{build_publisher, execute_script, TestPublisherBuilder};
+
+#[test]
+fn test_publisher_publish_entries() {
+    let (mut publisher_account, publisher_auth) = generate_publisher_account();
+
+    let entries = mock_entries(1_733_844_000);
+    let (entries_key, entries_values) = entries_advice(&entries).unwrap();
+
+    let executed_transaction = execute_publisher_script(
+        &publisher_account,
+        publisher_auth.clone(),
+        publish_entries_script(entries_key, entries.len()),
+        vec![(entries_key.into(), entries_values)],
+    )
+    .unwrap();
+    publisher_account
+        .apply_delta(executed_transaction.account_delta())
+        .unwrap();
+
+    for entry in &entries {
+        let entry_as_word: Word = entry.clone().try_into().unwrap();
+        assert_eq!(
+            publisher_account
+                .storage()
+                .get_map_item(
+                    publisher_account_slot(ENTRIES_MAP_SLOT),
+                    entry.pair.to_word()
+                )
+                .unwrap(),
+            entry_as_word
+        );
+    }
+
+    // The entries can be read back
+    for entry in entries {
+        execute_publisher_script(
+            &publisher_account,
+            publisher_auth.clone(),
+            publisher_get_entry_script(entry.pair.to_word()),
+            vec![],
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn test_publisher_publish_entry_stale_timestamp() {
+    let (mut publisher_account, publisher_auth) = generate_publisher_account();
+    let entry = mock_entries(1_733_844_000).remove(0);
+
+    let executed_transaction = execute_publisher_script(
+        &publisher_account,
+        publisher_auth.clone(),
+        publish_entry_script(&entry).unwrap(),
+        vec![],
+    )
+    .unwrap();
+    publisher_account
+        .apply_delta(executed_transaction.account_delta())
+        .unwrap();
+
+    // Replaying the same entry, or publishing an older one, must fail
+    for timestamp in [entry.timestamp, entry.timestamp - 1] {
+        let stale_entry = Entry {
+            timestamp,
+            ..entry.clone()
+        };
+        let result = execute_publisher_script(
+            &publisher_account,
+            publisher_auth.clone(),
+            publish_entry_script(&stale_entry).unwrap(),
+            vec![],
+        );
+        assert!(result.is_err());
+    }
+
+    let newer_entry = Entry {
+        timestamp: entry.timestamp + 1,
+        ..entry
+    };
+    execute_publisher_script(
+        &publisher_account,
+        publisher_auth,
+        publish_entry_script(&newer_entry).unwrap(),
+        vec![],
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_publisher_publish_entry_pair_mismatch() {
+    let (publisher_account, publisher_auth) = generate_publisher_account();
+    let entries = mock_entries(1_733_844_000);
+    let (btc_pair, btc_entry) = entry_to_words(&entries[0]).unwrap();
+    let (eth_pair, _) = entry_to_words(&entries[1]).unwrap();
+
+    // An ETH price under the BTC key, and a BTC key with a non-zero padding
+    let mut padded_btc_pair = btc_pair;
+    padded_btc_pair[0] = Felt::new(1);
+    for pair_as_word in [eth_pair, padded_btc_pair] {
+        let result = execute_publisher_script(
+            &publisher_account,
+            publisher_auth.clone(),
+            publish_words_script(pair_as_word, btc_entry),
+            vec![],
+        );
+        assert!(result.is_err());
+    }
+
+    // A batch containing a mismatched entry is rejected as a whole
+    let mut values = Vec::new();
+    values.extend(btc_pair);
+    values.extend(btc_entry);
+    values.extend(btc_pair);
+    values.extend(entry_to_words(&entries[1]).unwrap().1);
+    let entries_key = Rpo256::hash_elements(&values);
+    let result = execute_publisher_script(
+        &publisher_account,
+        publisher_auth,
+        publish_entries_script(entries_key, 2),
+        vec![(entries_key.into(), values)],
+    );
+    assert!(result.is_err());
+}
+
+// HELPERS
+// ================================================================================================
+
+pub fn generate_publisher_account() -> (Account, Arc<dyn TransactionAuthenticator>) {
+    build_publisher(TestPublisherBuilder::new(), [1_u8; 32])
+}
+
+/// Entries for distinct pairs, all published after `timestamp`.
+pub fn mock_entries(timestamp: u64) -> Vec<Entry> {
+    ["BTC/USD", "ETH/USD", "SOL/USD"]
+        .iter()
+        .zip(1..)
+        .map(|(pair, i)| Entry {
+            pair: Pair::from_str(pair).unwrap(),
+            price: 1_000_000 * i,
+            decimals: 6,
+            timestamp: timestamp + i,
+        })
+        .collect()
+}
+
+/// Script publishing arbitrary words, to check that malformed inputs are rejected.
+pub fn publish_words_script(pair_as_word: Word, entry_as_word: Word) -> String {
+    format!(
+        "
+        use.publisher_component::publisher_module
+        use.std::sys
+
+        begin
+            push.{entry}
+            push.{pair}
+            call.publisher_module::publish_entry
+            exec.sys::truncate_stack
+        end
+        ",
+        entry = word_to_masm(entry_as_word),
+        pair = word_to_masm(pair_as_word),
+    )
+}
+
+pub fn execute_publisher_script(
+    publisher_account: &Account,
+    publisher_auth: Arc<dyn TransactionAuthenticator>,
+    tx_script_code: String,
+    advice: Vec<(Word, Vec<Felt>)>,
+) -> Result<ExecutedTransaction, TransactionExecutorError> {
+    let tx_script = compile_publisher_script(tx_script_code, advice).unwrap();
+    execute_script(publisher_account, publisher_auth, tx_script, &[])
+}
@@ -0,0 +1,223 @@
+use colored::*;
+use miden_client::accounts::{Account, StorageSlot};
+use miden_client::crypto::{FeltRng, RpoDigest};
+use miden_client::transactions::{TransactionId, TransactionRequest};
+use miden_client::Client;
+use miden_crypto::dsa::rpo_falcon512::PublicKey;
+use pm_accounts::oracle::{
+    admin_signers_advice, oracle_account_slot, ADMINS_CONFIG_SLOT, ADMINS_MAP_SLOT,
+    OWNER_PUBLIC_KEY_SLOT,
+};
+use pm_accounts::scripts::{
+    add_admin_script, compile_oracle_script, remove_admin_script, set_admin_threshold_script,
+};
+use pm_accounts::utils::public_key_to_hex;
+use pm_utils_cli::{
+    execute_transaction, get_account, local_public_keys, parse_public_key, print_json,
+    submit_transaction, sync_client, ClientConfig, JsonStorage,
+};
+use prettytable::{Cell, Row, Table};
+use serde_json::json;
+
+#[derive(clap::Parser, Debug, Clone)]
+#[clap(about = "Manages the admins of the Oracle")]
+pub struct AdminsCmd {
+    #[command(subcommand)]
+    action: AdminsAction,
+    /// Public key of an admin signing the transaction, required once an admin threshold is set.
+    /// Its secret key must be held by an account of the local store.
+    #[clap(long = "signer", global = true, value_parser = parse_public_key)]
+    signers: Vec<PublicKey>,
+}
+
+#[derive(clap::Subcommand, Debug, Clone)]
+enum AdminsAction {
+    /// Shows the owner, the admins and the threshold
+    List,
+    /// Registers a new admin public key
+    Add {
+        #[clap(value_parser = parse_public_key)]
+        public_key: PublicKey,
+    },
+    /// Removes an admin public key
+    Remove {
+        #[clap(value_parser = parse_public_key)]
+        public_key: PublicKey,
+    },
+    /// Sets the number of admins required to sign, 0 gives the control back to the owner
+    Threshold { threshold: u32 },
+}
+
+impl AdminsCmd {
+    pub async fn call(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        client_config: &ClientConfig,
+    ) -> anyhow::Result<()> {
+        let tx_script_code = match &self.action {
+            AdminsAction::List => return list_admins(client, client_config).await,
+            AdminsAction::Add { public_key } => add_admin_script(*public_key),
+            AdminsAction::Remove { public_key } => remove_admin_script(*public_key),
+            AdminsAction::Threshold { threshold } => set_admin_threshold_script(*threshold),
+        };
+
+        let tx_id =
+            submit_admin_script(client, client_config, tx_script_code, &self.signers).await?;
+
+        if client_config.output.is_json() {
+            return print_json(&json!({ "tx_id": tx_id.to_string() }));
+        }
+
+        println!("✅ Admins updated!");
+
+        Ok(())
+    }
+}
+
+/// Submits an administration script to the oracle, with the public keys of the signing admins
+/// in the advice map. The whole transaction is signed by the local store, see
+/// [ensure_local_signers]. Returns the id of the submitted transaction.
+pub(crate) async fn submit_admin_script(
+    client: &mut Client<impl FeltRng>,
+    client_config: &ClientConfig,
+    tx_script_code: String,
+    signers: &[PublicKey],
+) -> anyhow::Result<TransactionId> {
+    let pragma_storage = JsonStorage::new(&client_config.accounts)?;
+    let oracle_id = pragma_storage.oracle_id()?;
+
+    let oracle = get_account(client, oracle_id).await?;
+    ensure_local_signers(client, &oracle, signers).await?;
+
+    let (signers_key, signers) = admin_signers_advice(signers);
+
+    let admin_script = compile_oracle_script(tx_script_code, vec![(signers_key.into(), signers)])?;
+
+    let transaction_request = TransactionRequest::new()
+        .with_custom_script(admin_script)
+        .map_err(|e| anyhow::anyhow!("Error while building transaction request: {e:?}"))?;
+
+    let tx_result = execute_transaction(client, oracle_id, transaction_request).await?;
+    let tx_id = submit_transaction(client, tx_result).await?;
+
+    Ok(tx_id)
+}
+
+/// Fails unless the local store can produce every signature an administration transaction
+/// needs: the owner one while the admin threshold is 0, the ones of `threshold` admins
+/// otherwise. Transactions are signed by the store authenticator of a single client, there is
+/// no flow collecting the signatures of admins on separate machines.
+pub(crate) async fn ensure_local_signers(
+    client: &mut Client<impl FeltRng>,
+    oracle: &Account,
+    signers: &[PublicKey],
+) -> anyhow::Result<()> {
+    let threshold = oracle
+        .storage()
+        .get_item(oracle_account_slot(ADMINS_CONFIG_SLOT))?[0]
+        .as_int();
+
+    let required_keys = if threshold == 0 {
+        let owner = oracle
+            .storage()
+            .get_item(oracle_account_slot(OWNER_PUBLIC_KEY_SLOT))?;
+        vec![PublicKey::new(owner.into())]
+    } else {
+        if (signers.len() as u64) < threshold {
+            anyhow::bail!(
+                "{threshold} admins must sign, pass their public keys with --signer ({} given)",
+                signers.len()
+            );
+        }
+        signers.to_vec()
+    };
+
+    let local_keys = local_public_keys(client).await?;
+    if let Some(missing) = required_keys
+        .into_iter()
+        .find(|key| !local_keys.contains(&(*key).into()))
+    {
+        anyhow::bail!(
+            "The secret key of {} is not in the local store. Administration transactions are \
+             signed by the local store, so all the signing keys must be on this machine",
+            public_key_to_hex(missing)
+        );
+    }
+
+    Ok(())
+}
+
+async fn list_admins(
+    client: &mut Client<impl FeltRng>,
+    client_config: &ClientConfig,
+) -> anyhow::Result<()> {
+    sync_client(client).await?;
+
+    let pragma_storage = JsonStorage::new(&client_config.accounts)?;
+    let oracle_id = pragma_storage.oracle_id()?;
+    let oracle = get_account(client, oracle_id).await?;
+
+    let owner = oracle
+        .storage()
+        .get_item(oracle_account_slot(OWNER_PUBLIC_KEY_SLOT))?;
+    let admins_config = oracle
+        .storage()
+        .get_item(oracle_account_slot(ADMINS_CONFIG_SLOT))?;
+    let threshold = admins_config[0].as_int();
+
+    let registered_admins =
+        match &oracle.storage().slots()[oracle_account_slot(ADMINS_MAP_SLOT) as usize] {
+            StorageSlot::Map(admins) => admins
+                .entries()
+                .filter(|(_, value)| value[0].as_int() == 1)
+                .map(|(admin, _)| *admin)
+                .collect::<Vec<RpoDigest>>(),
+            _ => Vec::new(),
+        };
+
+    if client_config.output.is_json() {
+        return print_json(&json!({
+            "owner": owner.to_hex(),
+            "threshold": threshold,
+            "admins": registered_admins.iter().map(|admin| admin.to_hex()).collect::<Vec<_>>(),
+        }));
+    }
+
+    println!(
+        "{}",
+        format!("🔑 Owner: {}", owner.to_hex()).bright_yellow()
+    );
+    println!(
+        "{}",
+        format!(
+            "🛡️  Threshold: {} of {} admins\n",
+            threshold,
+            admins_config[1].as_int()
+        )
+        .bright_yellow()
+    );
+
+    let mut table = Table::new();
+    table.set_format(*prettytable::format::consts::FORMAT_BOX_CHARS);
+    table.add_row(Row::new(vec![
+        Cell::new("Index").style_spec("Fcb"),
+        Cell::new("Admin Public Key").style_spec("Fcb"),
+    ]));
+    for (i, admin) in registered_admins.iter().enumerate() {
+        table.add_row(Row::new(vec![
+            Cell::new(&format!("{}", i + 1)).style_spec("Fg"),
+            Cell::new(&admin.to_hex()).style_spec("Fy"),
+        ]));
+    }
+
+    table.printstd();
+
+    if threshold == 0 {
+        println!(
+            "\n{}",
+            "ℹ️  No admin threshold set, the owner key administrates the Oracle.".bright_blue()
+        );
+    }
+
+    Ok(())
+}
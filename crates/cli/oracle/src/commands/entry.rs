@@ -1,35 +1,139 @@
-use std::str::FromStr;
+use std::time::Duration;
 
 use chrono::{DateTime, Utc};
+use colored::*;
 use miden_client::accounts::AccountId;
 use miden_client::crypto::FeltRng;
-use miden_client::Client;
+use miden_client::{Client, ZERO};
 use prettytable::{Cell, Row, Table};
 
+use pm_accounts::publisher::{publisher_account_slot, ENTRIES_MAP_SLOT};
 use pm_types::{Entry, Pair};
+use pm_utils_cli::{
+    entry_json, get_account, parse_interval, parse_pair, print_json, sync_client, ClientConfig,
+    JsonStorage,
+};
+use serde_json::json;
+
+use super::median::format_price;
+use super::watch::{change_indicator, clear_screen, format_age, now, Watcher};
 
 #[derive(clap::Parser, Debug, Clone)]
 #[clap(about = "Retrieve an entry for a given pair and publisher id ")]
 pub struct EntryCmd {
-    // The id of the publisher
-    publisher_id: String,
+    // The name or id of the publisher
+    publisher: String,
     // Input pair (format example: "BTC/USD")
-    pair: String,
+    #[clap(value_parser = parse_pair)]
+    pair: Pair,
+    /// Re-reads the entry at this interval (format example: "5s") until Ctrl+C
+    #[clap(long, value_parser = parse_interval)]
+    watch: Option<Duration>,
 }
 
 impl EntryCmd {
-    pub async fn call(&self, client: &mut Client<impl FeltRng>) -> anyhow::Result<()> {
-        client.sync_state().await.unwrap();
+    pub async fn call(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        client_config: &ClientConfig,
+    ) -> anyhow::Result<()> {
+        let pragma_storage = JsonStorage::new(&client_config.accounts)?;
+        let publisher_id = pragma_storage.publisher_id(Some(&self.publisher))?;
+
+        if let Some(period) = self.watch {
+            return self
+                .watch_entry(client, client_config, publisher_id, period)
+                .await;
+        }
+
+        let entry = self.fetch(client, publisher_id).await?;
+
+        if client_config.output.is_json() {
+            return print_json(&entry_json(publisher_id, &entry));
+        }
 
-        let publisher_id = AccountId::from_hex(&self.publisher_id).unwrap();
-        let (publisher, _) = client.get_account(publisher_id).await.unwrap();
+        self.entry_table(publisher_id, &entry)?.printstd();
+
+        Ok(())
+    }
 
-        let pair: Pair = Pair::from_str(&self.pair).unwrap();
-        let word = publisher.storage().get_map_item(2, pair.to_word()).unwrap();
+    /// Syncs the client, then reads the entry from the publisher storage.
+    async fn fetch(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        publisher_id: AccountId,
+    ) -> anyhow::Result<Entry> {
+        sync_client(client).await?;
+        let publisher = get_account(client, publisher_id).await?;
+
+        let word = publisher.storage().get_map_item(
+            publisher_account_slot(ENTRIES_MAP_SLOT),
+            self.pair.to_word(),
+        )?;
+        if word == [ZERO; 4] {
+            anyhow::bail!("{} has no entry for {}", publisher_id, self.pair);
+        }
 
         // Convert Word to Entry
-        let entry = Entry::from(word);
+        Ok(Entry::from(word))
+    }
+
+    /// Re-reads the entry every `period` until Ctrl+C, printing a line per refresh with the JSON
+    /// output. A failed refresh is reported and retried at the next one.
+    async fn watch_entry(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        client_config: &ClientConfig,
+        publisher_id: AccountId,
+        period: Duration,
+    ) -> anyhow::Result<()> {
+        let output = client_config.output;
+        let mut watcher = Watcher::new(period);
+        let mut previous_price = None;
+
+        while watcher.tick().await {
+            let entry = match self.fetch(client, publisher_id).await {
+                Ok(entry) => entry,
+                Err(e) => {
+                    eprintln!("{}", format!("❌ {e:#}").bright_red());
+                    continue;
+                }
+            };
+
+            if output.is_json() {
+                print_json(&json!({
+                    "event": "entry",
+                    "entry": entry_json(publisher_id, &entry),
+                    "timestamp": now(),
+                }))?;
+            } else {
+                let mut table = self.entry_table(publisher_id, &entry)?;
+                table.add_row(Row::new(vec![
+                    Cell::new("Change").style_spec("Fc"),
+                    Cell::new(&change_indicator(previous_price, entry.price).to_string()),
+                ]));
+                table.add_row(Row::new(vec![
+                    Cell::new("Age").style_spec("Fc"),
+                    Cell::new(&format_age(entry.timestamp, now())).style_spec("Fy"),
+                ]));
+
+                clear_screen();
+                table.printstd();
+                println!(
+                    "{}",
+                    format!("Refreshed every {:?}, Ctrl+C to stop", period).dimmed()
+                );
+            }
+            previous_price = Some(entry.price);
+        }
+
+        if !output.is_json() {
+            println!("{}", "👋 Stopped watching".bright_yellow());
+        }
+        Ok(())
+    }
 
+    fn entry_table(&self, publisher_id: AccountId, entry: &Entry) -> anyhow::Result<Table> {
         // Create and style table
         let mut table = Table::new();
         table.set_format(*prettytable::format::consts::FORMAT_BOX_CHARS);
@@ -37,7 +141,7 @@ impl EntryCmd {
         // Add publisher info
         table.add_row(Row::new(vec![
             Cell::new("Publisher ID").style_spec("Fc"),
-            Cell::new(&self.publisher_id).style_spec("Fy"),
+            Cell::new(&publisher_id.to_string()).style_spec("Fy"),
         ]));
 
         // Add pair info
@@ -46,17 +150,15 @@ impl EntryCmd {
             Cell::new(&format!("💱 {}", self.pair)).style_spec("Fy"),
         ]));
 
-        // Format price with proper decimals
-        let price_float = entry.price as f64 / 10f64.powi(entry.decimals as i32);
-        let price_formatted = format!("{:.width$}", price_float, width = entry.decimals as usize);
-
         table.add_row(Row::new(vec![
             Cell::new("Price").style_spec("Fc"),
-            Cell::new(&format!("💰 {}", price_formatted)).style_spec("Fy"),
+            Cell::new(&format!("💰 {}", format_price(entry.price, entry.decimals)))
+                .style_spec("Fy"),
         ]));
 
         // Convert timestamp to human-readable format
-        let dt = DateTime::<Utc>::from_timestamp(entry.timestamp as i64, 0).unwrap();
+        let dt = DateTime::<Utc>::from_timestamp(entry.timestamp as i64, 0)
+            .ok_or_else(|| anyhow::anyhow!("Invalid entry timestamp {}", entry.timestamp))?;
         let formatted_time = dt.format("%Y-%m-%d %H:%M:%S UTC").to_string();
 
         table.add_row(Row::new(vec![
@@ -64,9 +166,6 @@ impl EntryCmd {
             Cell::new(&format!("🕒 {}", formatted_time)).style_spec("Fy"),
         ]));
 
-        // Print the table
-        table.printstd();
-
-        Ok(())
+        Ok(table)
     }
 }
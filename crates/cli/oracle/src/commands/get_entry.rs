@@ -1,86 +1,61 @@
-use std::str::FromStr;
+use miden_client::crypto::FeltRng;
+use miden_client::transactions::TransactionRequest;
+use miden_client::Client;
 
-use miden_client::transactions::{TransactionKernel, TransactionRequest, TransactionScript};
-use miden_client::{accounts::AccountId, crypto::FeltRng};
-use miden_client::{Client, Felt, ZERO};
-
-use pm_accounts::oracle::get_oracle_component_library;
-use pm_accounts::utils::word_to_masm;
+use pm_accounts::scripts::{compile_oracle_script, oracle_get_entry_script};
 use pm_types::Pair;
 use pm_utils_cli::{
-    hex_to_decimal, JsonStorage, ORACLE_ACCOUNT_COLUMN, PRAGMA_ACCOUNTS_STORAGE_FILE,
-    PUBLISHER_ACCOUNT_COLUMN,
+    execute_transaction, parse_pair, print_json, submit_transaction, ClientConfig, JsonStorage,
 };
+use serde_json::json;
 
 #[derive(clap::Parser, Debug, Clone)]
 #[clap(about = "Gets entry")]
 pub struct GetEntryCmd {
     // Input pair (format example: "BTC/USD")
-    pair: String,
+    #[clap(value_parser = parse_pair)]
+    pair: Pair,
+    /// Name or id of the publisher, the default publisher when omitted
+    #[clap(long)]
+    publisher: Option<String>,
 }
 
 impl GetEntryCmd {
-    pub async fn call(&self, client: &mut Client<impl FeltRng>) -> anyhow::Result<()> {
-        let pragma_storage = JsonStorage::new(PRAGMA_ACCOUNTS_STORAGE_FILE)?;
-
-        let oracle_id = pragma_storage.get_key(ORACLE_ACCOUNT_COLUMN).unwrap();
-        let oracle_id = AccountId::from_hex(oracle_id).unwrap();
-
-        let publisher_id = pragma_storage.get_key(PUBLISHER_ACCOUNT_COLUMN).unwrap();
-        let publisher_id = AccountId::from_hex(publisher_id).unwrap();
+    pub async fn call(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        client_config: &ClientConfig,
+    ) -> anyhow::Result<()> {
+        let pragma_storage = JsonStorage::new(&client_config.accounts)?;
 
-        let pair: Pair = Pair::from_str(&self.pair).unwrap();
-        let tx_script_code = format!(
-            "
-            use.oracle_component::oracle_module
-            use.std::sys
-    
-            begin
-                push.{pair}
-                push.{publisher_id}
+        let oracle_id = pragma_storage.oracle_id()?;
 
-                call.oracle_module::get_entry
-    
-                exec.sys::truncate_stack
-            end
-            ",
-            pair = word_to_masm(pair.to_word()),
-            publisher_id = word_to_masm([
-                ZERO,
-                ZERO,
-                ZERO,
-                Felt::new(hex_to_decimal(&publisher_id.to_string())?),
-            ])
-        );
+        let publisher_id = pragma_storage.publisher_id(self.publisher.as_deref())?;
 
+        let pair = &self.pair;
         // TODO: Can we pipe stdout to a variable so we can see the stack??
 
-        let get_entry_script = TransactionScript::compile(
-            tx_script_code,
-            [],
-            TransactionKernel::assembler()
-                .with_library(get_oracle_component_library())
-                .map_err(|e| {
-                    anyhow::anyhow!("Error while setting up the component library: {e:?}")
-                })?,
-        )
-        .map_err(|e| anyhow::anyhow!("Error while compiling the script: {e:?}"))?;
+        let get_entry_script = compile_oracle_script(
+            oracle_get_entry_script(publisher_id.into(), pair.to_word()),
+            vec![],
+        )?;
 
         let transaction_request = TransactionRequest::new()
             .with_public_foreign_accounts([publisher_id])
-            .unwrap()
+            .map_err(|e| anyhow::anyhow!("Error while building transaction request: {e:?}"))?
             .with_custom_script(get_entry_script)
-            .unwrap();
-
-        let tx_result = client
-            .new_transaction(oracle_id, transaction_request)
-            .await
-            .map_err(|e| anyhow::anyhow!("Error while creating a transaction: {e:?}"))?;
-
-        client
-            .submit_transaction(tx_result.clone())
-            .await
-            .map_err(|e| anyhow::anyhow!("Error while submitting a transaction: {e:?}"))?;
+            .map_err(|e| anyhow::anyhow!("Error while building transaction request: {e:?}"))?;
+
+        let tx_result = execute_transaction(client, oracle_id, transaction_request).await?;
+        let tx_id = submit_transaction(client, tx_result).await?;
+
+        if client_config.output.is_json() {
+            return print_json(&json!({
+                "publisher_id": publisher_id.to_string(),
+                "pair": pair.to_string(),
+                "tx_id": tx_id.to_string(),
+            }));
+        }
 
         Ok(())
     }
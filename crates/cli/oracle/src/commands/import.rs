@@ -0,0 +1,39 @@
+use miden_client::accounts::AccountId;
+use miden_client::crypto::FeltRng;
+use miden_client::Client;
+use pm_utils_cli::{
+    import_oracle, parse_account_id, print_json, sync_client, ClientConfig, JsonStorage,
+};
+use serde_json::json;
+
+#[derive(clap::Parser, Debug, Clone)]
+#[clap(about = "Tracks an Oracle created elsewhere, for the read-only commands")]
+pub struct ImportCmd {
+    // The id of the oracle
+    #[clap(value_parser = parse_account_id)]
+    oracle_id: AccountId,
+}
+
+impl ImportCmd {
+    pub async fn call(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        client_config: &ClientConfig,
+    ) -> anyhow::Result<()> {
+        let oracle_id = self.oracle_id;
+
+        sync_client(client).await?;
+        import_oracle(client, oracle_id).await?;
+
+        let mut pragma_storage = JsonStorage::new(&client_config.accounts)?;
+        pragma_storage.set_oracle_id(oracle_id)?;
+
+        if client_config.output.is_json() {
+            return print_json(&json!({ "oracle_id": oracle_id.to_string() }));
+        }
+
+        println!("✅ Oracle {} imported!", oracle_id);
+
+        Ok(())
+    }
+}
@@ -1,26 +1,74 @@
+use std::path::PathBuf;
+
 use colored::*;
 use miden_client::crypto::FeltRng;
 use miden_client::Client;
-use pm_accounts::oracle::OracleAccountBuilder;
-use pm_utils_cli::{JsonStorage, ORACLE_ACCOUNT_COLUMN, PRAGMA_ACCOUNTS_STORAGE_FILE};
+use pm_accounts::oracle::{
+    oracle_account_slot, OracleAccountBuilder, OracleConfig, OWNER_PUBLIC_KEY_SLOT,
+};
+use pm_utils_cli::{print_json, read_seed_file, sync_client, ClientConfig, JsonStorage};
+use serde_json::json;
 
 #[derive(clap::Parser, Debug, Clone)]
 #[clap(about = "Creates a new Oracle Account")]
-pub struct InitCmd {}
+pub struct InitCmd {
+    /// Oracle config file (TOML or JSON) with the owner key, publishers, pairs & quorum
+    #[clap(long)]
+    config: Option<PathBuf>,
+    /// File holding a hex encoded 32 bytes seed, the same seed & config always create the same
+    /// Oracle id. The seed is secret: it derives the owner Falcon key unless the config provides
+    /// one, keep the file private
+    #[clap(long)]
+    seed_file: Option<PathBuf>,
+}
 
 impl InitCmd {
-    pub async fn call(&self, client: &mut Client<impl FeltRng>) -> anyhow::Result<()> {
-        println!("⏳ Initiating the Oracle...\n");
-        client.sync_state().await.unwrap();
-
-        let (oracle_account, _) = OracleAccountBuilder::new()
-            .with_client(client)
-            .build()
-            .await;
+    pub async fn call(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        client_config: &ClientConfig,
+    ) -> anyhow::Result<()> {
+        let config = match &self.config {
+            Some(path) => OracleConfig::from_file(path)?,
+            None => OracleConfig::default(),
+        };
+
+        let mut builder = OracleAccountBuilder::new().with_config(&config)?;
+        if let Some(seed_file) = &self.seed_file {
+            builder = builder.with_seed(read_seed_file(seed_file)?);
+        }
+
+        let output = client_config.output;
+        if !output.is_json() {
+            println!("⏳ Initiating the Oracle...\n");
+        }
+        sync_client(client).await?;
+
+        let (oracle_account, _) = builder.with_client(client).build().await?;
         let created_oracle_id = oracle_account.id();
-
-        let mut pragma_storage = JsonStorage::new(PRAGMA_ACCOUNTS_STORAGE_FILE)?;
-        pragma_storage.add_key(ORACLE_ACCOUNT_COLUMN, &created_oracle_id.to_string())?;
+        let owner_public_key = oracle_account
+            .storage()
+            .get_item(oracle_account_slot(OWNER_PUBLIC_KEY_SLOT))?;
+
+        let mut pragma_storage = JsonStorage::new(&client_config.accounts)?;
+        pragma_storage.set_oracle_id(created_oracle_id)?;
+        let pairs: Vec<String> = config
+            .pairs()?
+            .iter()
+            .map(|pair| pair.pair.to_string())
+            .collect();
+        pragma_storage.set_pairs(pairs.clone())?;
+
+        if output.is_json() {
+            return print_json(&json!({
+                "oracle_id": created_oracle_id.to_string(),
+                "accounts": client_config.accounts,
+                "publishers": config.publishers.len(),
+                "pairs": pairs,
+                "quorum": config.quorum,
+                "owner_public_key": owner_public_key.to_hex(),
+            }));
+        }
 
         println!();
 
@@ -58,9 +106,15 @@ impl InitCmd {
         ╭────────────────────────────────────────────────────────────╮
         │ Oracle ID: {}
         │ Storage Location: {}
+        │ Registered Publishers: {}
+        │ Quorum: {}
+        │ Owner Public Key: {}
         ╰────────────────────────────────────────────────────────────╯",
                 created_oracle_id.to_string().bright_white(),
-                PRAGMA_ACCOUNTS_STORAGE_FILE.bright_white()
+                client_config.accounts.display().to_string().bright_white(),
+                config.publishers.len().to_string().bright_white(),
+                config.quorum.to_string().bright_white(),
+                owner_public_key.to_hex().bright_white()
             )
             .bright_blue()
         );
@@ -0,0 +1,71 @@
+use std::path::PathBuf;
+
+use miden_client::crypto::FeltRng;
+use miden_client::Client;
+use pm_accounts::oracle::verify_oracle_account;
+use pm_utils_cli::{
+    export_account_keys, import_account_keys, print_json, read_key_password, ClientConfig,
+    JsonStorage,
+};
+use serde_json::json;
+
+#[derive(clap::Parser, Debug, Clone)]
+#[clap(about = "Exports or imports the Oracle account with its owner key")]
+pub struct KeysCmd {
+    #[command(subcommand)]
+    action: KeysAction,
+}
+
+#[derive(clap::Subcommand, Debug, Clone)]
+enum KeysAction {
+    /// Writes the Oracle account & its owner secret key to a password-encrypted file
+    Export {
+        #[clap(long, default_value = "oracle.key")]
+        file: PathBuf,
+    },
+    /// Restores the Oracle account & its owner secret key from an encrypted file
+    Import { file: PathBuf },
+}
+
+impl KeysCmd {
+    pub async fn call(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        client_config: &ClientConfig,
+    ) -> anyhow::Result<()> {
+        match &self.action {
+            KeysAction::Export { file } => {
+                let pragma_storage = JsonStorage::new(&client_config.accounts)?;
+                let oracle_id = pragma_storage.oracle_id()?;
+                let password = read_key_password(true)?;
+                export_account_keys(client, oracle_id, file, &password).await?;
+
+                if client_config.output.is_json() {
+                    return print_json(
+                        &json!({ "oracle_id": oracle_id.to_string(), "file": file }),
+                    );
+                }
+
+                println!("✅ Oracle {} exported to {}", oracle_id, file.display());
+            }
+            KeysAction::Import { file } => {
+                let password = read_key_password(false)?;
+                let oracle = import_account_keys(client, file, &password).await?;
+                verify_oracle_account(&oracle)?;
+
+                let mut pragma_storage = JsonStorage::new(&client_config.accounts)?;
+                pragma_storage.set_oracle_id(oracle.id())?;
+
+                if client_config.output.is_json() {
+                    return print_json(
+                        &json!({ "oracle_id": oracle.id().to_string(), "file": file }),
+                    );
+                }
+
+                println!("✅ Oracle {} imported from {}", oracle.id(), file.display());
+            }
+        }
+
+        Ok(())
+    }
+}
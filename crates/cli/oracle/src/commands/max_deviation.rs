@@ -0,0 +1,42 @@
+use miden_client::crypto::FeltRng;
+use miden_client::Client;
+use miden_crypto::dsa::rpo_falcon512::PublicKey;
+use pm_accounts::scripts::set_max_deviation_script;
+use pm_utils_cli::{parse_public_key, print_json, ClientConfig};
+use serde_json::json;
+
+use super::admins::submit_admin_script;
+
+#[derive(clap::Parser, Debug, Clone)]
+#[clap(about = "Sets the max deviation, in basis points, accepted by the checked median")]
+pub struct SetMaxDeviationCmd {
+    /// Max move of the median compared to the last checked one, 0 disables the check
+    max_deviation_bps: u32,
+    /// Public key of an admin signing the transaction, required once an admin threshold is set.
+    /// Its secret key must be held by an account of the local store.
+    #[clap(long = "signer", value_parser = parse_public_key)]
+    signers: Vec<PublicKey>,
+}
+
+impl SetMaxDeviationCmd {
+    pub async fn call(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        client_config: &ClientConfig,
+    ) -> anyhow::Result<()> {
+        let tx_script_code = set_max_deviation_script(self.max_deviation_bps);
+        let tx_id =
+            submit_admin_script(client, client_config, tx_script_code, &self.signers).await?;
+
+        if client_config.output.is_json() {
+            return print_json(&json!({
+                "max_deviation_bps": self.max_deviation_bps,
+                "tx_id": tx_id.to_string(),
+            }));
+        }
+
+        println!("✅ Max deviation set to {} bps", self.max_deviation_bps);
+
+        Ok(())
+    }
+}
@@ -1,79 +1,388 @@
-use miden_client::transactions::{TransactionKernel, TransactionRequest, TransactionScript};
+use std::time::Duration;
+
+use colored::*;
+use miden_client::accounts::Account;
+use miden_client::transactions::{TransactionId, TransactionRequest};
 use miden_client::Client;
-use miden_client::{accounts::AccountId, crypto::FeltRng};
-use pm_accounts::oracle::get_oracle_component_library;
-use pm_accounts::utils::word_to_masm;
-use pm_types::Pair;
+use miden_client::{accounts::AccountId, crypto::FeltRng, ZERO};
+use miden_crypto::dsa::rpo_falcon512::PublicKey;
+use pm_accounts::oracle::{
+    admin_signers_advice, oracle_account_slot, pair_publisher_key, verify_oracle_procedures,
+    PAIRS_MAP_SLOT, PAIR_PUBLISHERS_MAP_SLOT,
+};
+use pm_accounts::publisher::{publisher_account_slot, ENTRIES_MAP_SLOT};
+use pm_accounts::scripts::{compile_oracle_script, expected_median_script, median_procedure};
+use pm_types::{median, Entry, Pair, PublisherId};
 use pm_utils_cli::{
-    JsonStorage, ORACLE_ACCOUNT_COLUMN, PRAGMA_ACCOUNTS_STORAGE_FILE, PUBLISHER_ACCOUNT_COLUMN,
+    entry_json, execute_transaction, get_or_import_account, parse_interval, parse_pair,
+    parse_public_key, print_json, submit_transaction, sync_client, ClientConfig, JsonStorage,
 };
-use std::str::FromStr;
+use prettytable::{Cell, Row, Table};
+use serde_json::json;
+
+use super::admins::ensure_local_signers;
+use super::publishers::registered_publishers;
+use super::watch::{change_indicator, clear_screen, format_age, now, relative_change, Watcher};
 
 #[derive(clap::Parser, Debug, Clone)]
 #[clap(about = "Compute the median for a given pair")]
 pub struct MedianCmd {
     // Input pair (format example: "BTC/USD")
-    pair: String,
+    #[clap(value_parser = parse_pair)]
+    pair: Pair,
+    /// Fails if the median moved more than the max deviation from the last checked median,
+    /// the new median is then stored as the last checked one. Must be signed by the oracle
+    /// administration
+    #[clap(long)]
+    checked: bool,
+    /// Public key of an admin signing the checked median, required once an admin threshold is
+    /// set. Its secret key must be held by an account of the local store.
+    #[clap(long = "signer", value_parser = parse_public_key, requires = "checked")]
+    signers: Vec<PublicKey>,
+    /// Re-computes the median at this interval (format example: "5s") until Ctrl+C, without
+    /// submitting any transaction
+    #[clap(long, value_parser = parse_interval, conflicts_with = "checked")]
+    watch: Option<Duration>,
+}
+
+/// Median of a pair, as returned by the oracle, with the entries it was computed from.
+struct PairMedian {
+    median: u64,
+    pair_entries: PairEntries,
+    tx_id: Option<TransactionId>,
 }
 
 impl MedianCmd {
-    pub async fn call(&self, client: &mut Client<impl FeltRng>) -> anyhow::Result<()> {
-        let pragma_storage = JsonStorage::new(PRAGMA_ACCOUNTS_STORAGE_FILE)?;
-
-        let oracle_id = pragma_storage.get_key(ORACLE_ACCOUNT_COLUMN).unwrap();
-        let oracle_id = AccountId::from_hex(oracle_id).unwrap();
-
-        let publisher_id = pragma_storage.get_key(PUBLISHER_ACCOUNT_COLUMN).unwrap();
-        let publisher_id = AccountId::from_hex(publisher_id).unwrap();
-        let (publisher, _) = client.get_account(publisher_id).await.unwrap();
-
-        let pair: Pair = Pair::from_str(&self.pair).unwrap();
-        let tx_script_code = format!(
-            "
-            use.oracle_component::oracle_module
-            use.std::sys
-    
-            begin
-                push.{pair}
-                call.oracle_module::get_median
-                debug.stack
-                exec.sys::truncate_stack
-            end
-            ",
-            pair = word_to_masm(pair.to_word()),
+    pub async fn call(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        client_config: &ClientConfig,
+    ) -> anyhow::Result<()> {
+        let pragma_storage = JsonStorage::new(&client_config.accounts)?;
+
+        let oracle_id = pragma_storage.oracle_id()?;
+
+        if let Some(period) = self.watch {
+            return self
+                .watch_median(client, client_config, oracle_id, period)
+                .await;
+        }
+
+        let pair = &self.pair;
+        let PairMedian {
+            median,
+            pair_entries,
+            tx_id,
+        } = self.compute(client, oracle_id, true).await?;
+
+        if client_config.output.is_json() {
+            return print_json(&json!({
+                "pair": pair.to_string(),
+                "median": median,
+                "decimals": pair_entries.decimals,
+                "nb_of_entries": pair_entries.entries.len(),
+                "checked": self.checked,
+                "tx_id": tx_id.map(|tx_id| tx_id.to_string()),
+            }));
+        }
+
+        println!(
+            "{}",
+            format!(
+                "📊 {} median: {} ({} entries)",
+                pair,
+                format_price(median, pair_entries.decimals),
+                pair_entries.entries.len()
+            )
+            .bright_green()
         );
 
-        // TODO: Can we pipe stdout to a variable so we can see the stack??
-
-        let median_script = TransactionScript::compile(
-            tx_script_code,
-            [],
-            TransactionKernel::testing_assembler()
-                .with_debug_mode(true)
-                .with_library(get_oracle_component_library())
-                .map_err(|e| {
-                    anyhow::anyhow!("Error while setting up the component library: {e:?}")
-                })?
-                .clone(),
-        )
-        .map_err(|e| anyhow::anyhow!("Error while compiling the script: {e:?}"))?;
+        Ok(())
+    }
+
+    /// Syncs the client, then computes the median off-chain from the publisher entries and
+    /// executes the oracle with a script asserting it returns that same median, so the printed
+    /// median is the one of the oracle. The transaction is submitted when `submit` is set.
+    async fn compute(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        oracle_id: AccountId,
+        submit: bool,
+    ) -> anyhow::Result<PairMedian> {
+        sync_client(client).await?;
+
+        // Only the median procedure is called, the publishers registered in the oracle being
+        // read through foreign procedure calls
+        let oracle = get_or_import_account(client, oracle_id).await?;
+        verify_oracle_procedures(&oracle, &[median_procedure(self.checked)])?;
+        let publisher_ids = registered_publishers(oracle.storage())?
+            .into_iter()
+            .filter(|(_, is_registered)| *is_registered)
+            .map(|(publisher_id, _)| publisher_id.account_id())
+            .collect::<Vec<AccountId>>();
+
+        let pair = &self.pair;
+        let pair_entries = pair_entries(client, &oracle, pair).await?;
+
+        let prices: Vec<u64> = pair_entries
+            .entries
+            .iter()
+            .map(|(_, entry)| entry.price)
+            .collect();
+        let median = median(&prices).ok_or_else(|| anyhow::anyhow!("No entry for {pair}"))?;
+
+        let advice = if self.checked {
+            ensure_local_signers(client, &oracle, &self.signers).await?;
+            let (signers_key, signers) = admin_signers_advice(&self.signers);
+            vec![(signers_key.into(), signers)]
+        } else {
+            vec![]
+        };
+        let tx_script = compile_oracle_script(
+            expected_median_script(pair.to_word(), self.checked, median),
+            advice,
+        )?;
 
         let transaction_request = TransactionRequest::new()
-            .with_custom_script(median_script)
-            .unwrap()
-            .with_public_foreign_accounts([publisher.id()])
-            .unwrap();
+            .with_custom_script(tx_script)
+            .map_err(|e| anyhow::anyhow!("Error while building transaction request: {e:?}"))?
+            .with_public_foreign_accounts(publisher_ids)
+            .map_err(|e| anyhow::anyhow!("Error while building transaction request: {e:?}"))?;
 
-        let tx_result = client
-            .new_transaction(oracle_id, transaction_request)
+        // The execution fails when the pair is paused, the quorum isn't reached, or the oracle
+        // median differs from the off-chain one (`ERR_MEDIAN_MISMATCH`, e.g. an entry was
+        // published since the sync)
+        let tx_result = execute_transaction(client, oracle_id, transaction_request)
             .await
-            .map_err(|e| anyhow::anyhow!("Error while creating a transaction: {e:?}"))?;
+            .map_err(|e| anyhow::anyhow!("Error while computing the {pair} median: {e}"))?;
 
-        client
-            .submit_transaction(tx_result.clone())
-            .await
-            .map_err(|e| anyhow::anyhow!("Error while submitting a transaction: {e:?}"))?;
+        let tx_id = if submit {
+            Some(submit_transaction(client, tx_result).await?)
+        } else {
+            None
+        };
 
+        Ok(PairMedian {
+            median,
+            pair_entries,
+            tx_id,
+        })
+    }
+
+    /// Re-computes the median every `period` until Ctrl+C, printing a line per refresh with the
+    /// JSON output. A failed refresh is reported and retried at the next one.
+    async fn watch_median(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        client_config: &ClientConfig,
+        oracle_id: AccountId,
+        period: Duration,
+    ) -> anyhow::Result<()> {
+        let output = client_config.output;
+        let mut watcher = Watcher::new(period);
+        let mut previous: Option<PairMedian> = None;
+
+        while watcher.tick().await {
+            let pair_median = match self.compute(client, oracle_id, false).await {
+                Ok(pair_median) => pair_median,
+                Err(e) => {
+                    eprintln!("{}", format!("❌ {e:#}").bright_red());
+                    continue;
+                }
+            };
+
+            if output.is_json() {
+                let entries: Vec<_> = pair_median
+                    .pair_entries
+                    .entries
+                    .iter()
+                    .map(|(publisher_id, entry)| entry_json(*publisher_id, entry))
+                    .collect();
+                print_json(&json!({
+                    "event": "median",
+                    "pair": self.pair.to_string(),
+                    "median": pair_median.median,
+                    "decimals": pair_median.pair_entries.decimals,
+                    "entries": entries,
+                    "timestamp": now(),
+                }))?;
+            } else {
+                clear_screen();
+                print_median_table(&self.pair, &pair_median, previous.as_ref(), period);
+            }
+            previous = Some(pair_median);
+        }
+
+        if !output.is_json() {
+            println!("{}", "👋 Stopped watching".bright_yellow());
+        }
         Ok(())
     }
 }
+
+/// Prints the median with the contribution of each publisher, and the moves since the previous
+/// refresh.
+fn print_median_table(
+    pair: &Pair,
+    current: &PairMedian,
+    previous: Option<&PairMedian>,
+    period: Duration,
+) {
+    let PairMedian {
+        median,
+        pair_entries,
+        ..
+    } = current;
+    let decimals = pair_entries.decimals;
+    let now = now();
+
+    println!(
+        "📊 {} median: {} {}",
+        pair.to_string().bright_cyan(),
+        format_price(*median, decimals).bright_white(),
+        change_indicator(previous.map(|previous| previous.median), *median)
+    );
+    println!(
+        "{}",
+        format!(
+            "{} entries from {} publishers, refreshed every {:?}, Ctrl+C to stop\n",
+            pair_entries.entries.len(),
+            pair_entries.publishers.len(),
+            period
+        )
+        .dimmed()
+    );
+
+    let prices: Vec<u64> = pair_entries
+        .entries
+        .iter()
+        .map(|(_, entry)| entry.price)
+        .collect();
+    let middle_prices = middle_prices(&prices);
+
+    let mut table = Table::new();
+    table.set_format(*prettytable::format::consts::FORMAT_BOX_CHARS);
+    table.add_row(Row::new(vec![
+        Cell::new("Publisher").style_spec("Fcb"),
+        Cell::new("Price").style_spec("Fcb"),
+        Cell::new("Change").style_spec("Fcb"),
+        Cell::new("vs Median").style_spec("Fcb"),
+        Cell::new("Age").style_spec("Fcb"),
+        Cell::new("Contribution").style_spec("Fcb"),
+    ]));
+
+    for publisher_id in &pair_entries.publishers {
+        let entry = pair_entries
+            .entries
+            .iter()
+            .find(|(id, _)| id == publisher_id)
+            .map(|(_, entry)| entry);
+        let Some(entry) = entry else {
+            table.add_row(Row::new(vec![
+                Cell::new(&publisher_id.to_string()).style_spec("Fy"),
+                Cell::new("-"),
+                Cell::new("-"),
+                Cell::new("-"),
+                Cell::new("-"),
+                Cell::new("no entry").style_spec("Fr"),
+            ]));
+            continue;
+        };
+
+        let previous_price = previous.and_then(|previous| {
+            previous
+                .pair_entries
+                .entries
+                .iter()
+                .find(|(id, _)| id == publisher_id)
+                .map(|(_, entry)| entry.price)
+        });
+        let contribution = if middle_prices.contains(&entry.price) {
+            "median"
+        } else {
+            "included"
+        };
+
+        table.add_row(Row::new(vec![
+            Cell::new(&publisher_id.to_string()).style_spec("Fy"),
+            Cell::new(&format_price(entry.price, decimals)),
+            Cell::new(&change_indicator(previous_price, entry.price).to_string()),
+            Cell::new(&format!("{:+.2}%", relative_change(*median, entry.price))),
+            Cell::new(&format_age(entry.timestamp, now)),
+            Cell::new(contribution).style_spec("Fg"),
+        ]));
+    }
+
+    table.printstd();
+}
+
+/// Publishers allowed to publish a pair, with the entries they published.
+pub(crate) struct PairEntries {
+    /// Decimals of the pair prices.
+    pub decimals: u32,
+    pub publishers: Vec<AccountId>,
+    /// Entries of the publishers, publishers that never published the pair are skipped like
+    /// `compute_median` does.
+    pub entries: Vec<(AccountId, Entry)>,
+}
+
+/// Reads the publishers of a pair from the oracle storage, then their entries from the
+/// publisher accounts, importing the ones that aren't tracked yet.
+pub(crate) async fn pair_entries(
+    client: &mut Client<impl FeltRng>,
+    oracle: &Account,
+    pair: &Pair,
+) -> anyhow::Result<PairEntries> {
+    let pair_word = pair.to_word();
+
+    let pair_config = oracle
+        .storage()
+        .get_map_item(oracle_account_slot(PAIRS_MAP_SLOT), pair_word)?;
+    if pair_config[0] == ZERO {
+        anyhow::bail!("{pair} is not supported by the oracle {}", oracle.id());
+    }
+
+    let mut publishers = Vec::new();
+    let mut entries = Vec::new();
+    for i in 0..pair_config[2].as_int() as u32 {
+        let publisher_word = oracle.storage().get_map_item(
+            oracle_account_slot(PAIR_PUBLISHERS_MAP_SLOT),
+            pair_publisher_key(pair_word, i).into(),
+        )?;
+        let publisher_id = PublisherId::from_word(publisher_word)?.account_id();
+        publishers.push(publisher_id);
+
+        let publisher = get_or_import_account(client, publisher_id).await?;
+        let entry = publisher
+            .storage()
+            .get_map_item(publisher_account_slot(ENTRIES_MAP_SLOT), pair_word)?;
+        if entry != [ZERO; 4] {
+            entries.push((publisher_id, Entry::from(entry)));
+        }
+    }
+
+    Ok(PairEntries {
+        decimals: pair_config[1].as_int() as u32,
+        publishers,
+        entries,
+    })
+}
+
+/// Prices the median is computed from: the middle price, or the two middle ones for an even
+/// number of prices.
+fn middle_prices(prices: &[u64]) -> Vec<u64> {
+    let mut prices = prices.to_vec();
+    prices.sort_unstable();
+    let middle = prices.len() / 2;
+    match prices.len() {
+        0 => vec![],
+        len if len % 2 == 1 => vec![prices[middle]],
+        _ => vec![prices[middle - 1], prices[middle]],
+    }
+}
+
+/// Formats a price with its decimals (example: 9758812 with 2 decimals is "97588.12").
+pub(crate) fn format_price(price: u64, decimals: u32) -> String {
+    let price_float = price as f64 / 10f64.powi(decimals as i32);
+    format!("{:.width$}", price_float, width = decimals as usize)
+}
@@ -1,28 +1,51 @@
+mod admins;
 mod entry;
 mod get_entry;
+mod import;
 mod init;
+mod keys;
+mod max_deviation;
 mod median;
+mod pairs;
+mod pause;
 mod publishers;
 mod register_publisher;
+mod status;
 mod sync;
+mod transfer_ownership;
+mod watch;
 
 use clap::Parser;
 
+use admins::AdminsCmd;
 use entry::EntryCmd;
 use get_entry::GetEntryCmd;
+use import::ImportCmd;
 use init::InitCmd;
+use keys::KeysCmd;
+use max_deviation::SetMaxDeviationCmd;
 use median::MedianCmd;
+use pairs::PairsCmd;
+use pause::{PauseCmd, UnpauseCmd};
 use publishers::PublishersCmd;
 use register_publisher::RegisterPublisherCmd;
+use status::StatusCmd;
 use sync::SyncCmd;
+use transfer_ownership::TransferOwnershipCmd;
 
-use pm_utils_cli::setup_client;
+use pm_utils_cli::{setup_client, ClientConfig};
 
 #[derive(Debug, Parser, Clone)]
 pub enum SubCommand {
     // Init an Oracle account
     #[clap(name = "init", bin_name = "init")]
     Init(InitCmd),
+    // Export or import an account with its key
+    #[clap(name = "keys", bin_name = "keys")]
+    Keys(KeysCmd),
+    // Track an Oracle account created elsewhere
+    #[clap(name = "import", bin_name = "import")]
+    Import(ImportCmd),
     // Sync the local state with the node
     #[clap(name = "sync", bin_name = "sync")]
     Sync(SyncCmd),
@@ -38,6 +61,27 @@ pub enum SubCommand {
     // Shows the registered publishers
     #[clap(name = "publishers", bin_name = "publishers")]
     Publishers(PublishersCmd),
+    // Shows the latest prices of every pair & publisher
+    #[clap(name = "status", bin_name = "status")]
+    Status(StatusCmd),
+    // Transfer the ownership of the Oracle
+    #[clap(name = "transfer-ownership", bin_name = "transfer-ownership")]
+    TransferOwnership(TransferOwnershipCmd),
+    // Manage the admins of the Oracle
+    #[clap(name = "admins", bin_name = "admins")]
+    Admins(AdminsCmd),
+    // Pause a pair or the whole Oracle
+    #[clap(name = "pause", bin_name = "pause")]
+    Pause(PauseCmd),
+    // Unpause a pair or the whole Oracle
+    #[clap(name = "unpause", bin_name = "unpause")]
+    Unpause(UnpauseCmd),
+    // Set the max deviation of the checked medians
+    #[clap(name = "set-max-deviation", bin_name = "set-max-deviation")]
+    SetMaxDeviation(SetMaxDeviationCmd),
+    // Manage the pairs supported by the Oracle
+    #[clap(name = "pairs", bin_name = "pairs")]
+    Pairs(PairsCmd),
     // TO BE REMOVED
     // Get an entry for a given pair id
     #[clap(name = "get-entry", bin_name = "get-entry")]
@@ -45,17 +89,26 @@ pub enum SubCommand {
 }
 
 impl SubCommand {
-    pub async fn call(&self) -> anyhow::Result<()> {
-        let mut client = setup_client().await;
+    pub async fn call(&self, client_config: &ClientConfig) -> anyhow::Result<()> {
+        let mut client = setup_client(client_config).await?;
 
         match self {
-            Self::Init(cmd) => cmd.call(&mut client).await?,
-            Self::Sync(cmd) => cmd.call(&mut client).await?,
-            Self::RegisterPublisher(cmd) => cmd.call(&mut client).await?,
-            Self::Entry(cmd) => cmd.call(&mut client).await?,
-            Self::Median(cmd) => cmd.call(&mut client).await?,
-            Self::Publishers(cmd) => cmd.call(&mut client).await?,
-            Self::GetEntry(cmd) => cmd.call(&mut client).await?,
+            Self::Init(cmd) => cmd.call(&mut client, client_config).await?,
+            Self::Keys(cmd) => cmd.call(&mut client, client_config).await?,
+            Self::Import(cmd) => cmd.call(&mut client, client_config).await?,
+            Self::Sync(cmd) => cmd.call(&mut client, client_config).await?,
+            Self::RegisterPublisher(cmd) => cmd.call(&mut client, client_config).await?,
+            Self::Entry(cmd) => cmd.call(&mut client, client_config).await?,
+            Self::Median(cmd) => cmd.call(&mut client, client_config).await?,
+            Self::Publishers(cmd) => cmd.call(&mut client, client_config).await?,
+            Self::Status(cmd) => cmd.call(&mut client, client_config).await?,
+            Self::TransferOwnership(cmd) => cmd.call(&mut client, client_config).await?,
+            Self::Admins(cmd) => cmd.call(&mut client, client_config).await?,
+            Self::Pause(cmd) => cmd.call(&mut client, client_config).await?,
+            Self::Unpause(cmd) => cmd.call(&mut client, client_config).await?,
+            Self::SetMaxDeviation(cmd) => cmd.call(&mut client, client_config).await?,
+            Self::Pairs(cmd) => cmd.call(&mut client, client_config).await?,
+            Self::GetEntry(cmd) => cmd.call(&mut client, client_config).await?,
         }
 
         Ok(())
@@ -0,0 +1,247 @@
+use colored::*;
+use miden_client::accounts::{Account, AccountId, StorageSlot};
+use miden_client::crypto::FeltRng;
+use miden_client::{Client, ZERO};
+use miden_crypto::dsa::rpo_falcon512::PublicKey;
+use pm_accounts::oracle::{
+    oracle_account_slot, pair_publisher_key, DEFAULT_PAIR_DECIMALS, PAIRS_MAP_SLOT,
+    PAIR_PUBLISHERS_MAP_SLOT,
+};
+use pm_accounts::scripts::{add_pair_publisher_script, add_pair_script, remove_pair_script};
+use pm_types::{Pair, PublisherId};
+use pm_utils_cli::{
+    get_account, parse_account_id, parse_pair, parse_public_key, print_json, sync_client,
+    ClientConfig, JsonStorage,
+};
+use prettytable::{Cell, Row, Table};
+use serde_json::json;
+
+use super::admins::submit_admin_script;
+
+#[derive(clap::Parser, Debug, Clone)]
+#[clap(about = "Manages the pairs supported by the Oracle")]
+pub struct PairsCmd {
+    #[command(subcommand)]
+    action: PairsAction,
+    /// Public key of an admin signing the transactions, required once an admin threshold is set.
+    /// Its secret key must be held by an account of the local store.
+    #[clap(long = "signer", global = true, value_parser = parse_public_key)]
+    signers: Vec<PublicKey>,
+}
+
+#[derive(clap::Subcommand, Debug, Clone)]
+enum PairsAction {
+    /// Shows the supported pairs with their publishers
+    List,
+    /// Adds a pair, or allows new publishers to publish an existing pair
+    Add {
+        // Input pair (format example: "BTC/USD")
+        #[clap(value_parser = parse_pair)]
+        pair: Pair,
+        /// Decimals of the pair prices
+        #[clap(long, default_value_t = DEFAULT_PAIR_DECIMALS)]
+        decimals: u32,
+        /// Registered publisher allowed to publish the pair
+        #[clap(long = "publisher", value_parser = parse_account_id)]
+        publishers: Vec<AccountId>,
+    },
+    /// Removes a pair, its prices can't be read anymore
+    Remove {
+        // Input pair (format example: "BTC/USD")
+        #[clap(value_parser = parse_pair)]
+        pair: Pair,
+    },
+}
+
+impl PairsCmd {
+    pub async fn call(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        client_config: &ClientConfig,
+    ) -> anyhow::Result<()> {
+        match &self.action {
+            PairsAction::List => list_pairs(client, client_config).await,
+            PairsAction::Add {
+                pair,
+                decimals,
+                publishers,
+            } => {
+                self.add_pair(client, client_config, pair, *decimals, publishers)
+                    .await
+            }
+            PairsAction::Remove { pair } => self.remove_pair(client, client_config, pair).await,
+        }
+    }
+
+    async fn add_pair(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        client_config: &ClientConfig,
+        pair: &Pair,
+        decimals: u32,
+        publishers: &[AccountId],
+    ) -> anyhow::Result<()> {
+        let pair_word = pair.to_word();
+        let oracle_id = get_oracle_id(client_config)?;
+        let oracle = get_account(client, oracle_id).await?;
+        let output = client_config.output;
+        let mut tx_ids = Vec::new();
+
+        // The pair may already be supported when only adding publishers
+        let pair_config = oracle
+            .storage()
+            .get_map_item(oracle_account_slot(PAIRS_MAP_SLOT), pair_word)?;
+        if pair_config[0] == ZERO {
+            let tx_script_code = add_pair_script(pair_word, decimals);
+            tx_ids.push(
+                submit_admin_script(client, client_config, tx_script_code, &self.signers).await?,
+            );
+
+            let mut pragma_storage = JsonStorage::new(&client_config.accounts)?;
+            pragma_storage.add_pair(&pair.to_string())?;
+
+            if !output.is_json() {
+                println!("✅ {} added with {} decimals", pair, decimals);
+            }
+        }
+
+        for publisher_id in publishers {
+            let tx_script_code =
+                add_pair_publisher_script(pair_word, PublisherId::from(*publisher_id));
+            tx_ids.push(
+                submit_admin_script(client, client_config, tx_script_code, &self.signers).await?,
+            );
+
+            if !output.is_json() {
+                println!("✅ {} can now publish {}", publisher_id, pair);
+            }
+        }
+
+        if output.is_json() {
+            return print_json(&json!({
+                "pair": pair.to_string(),
+                "publishers": publishers
+                    .iter()
+                    .map(|publisher_id| publisher_id.to_string())
+                    .collect::<Vec<_>>(),
+                "tx_ids": tx_ids.iter().map(|tx_id| tx_id.to_string()).collect::<Vec<_>>(),
+            }));
+        }
+
+        Ok(())
+    }
+
+    async fn remove_pair(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        client_config: &ClientConfig,
+        pair: &Pair,
+    ) -> anyhow::Result<()> {
+        let pair_word = pair.to_word();
+        let tx_script_code = remove_pair_script(pair_word);
+        let tx_id =
+            submit_admin_script(client, client_config, tx_script_code, &self.signers).await?;
+
+        let mut pragma_storage = JsonStorage::new(&client_config.accounts)?;
+        pragma_storage.remove_pair(&pair.to_string())?;
+
+        if client_config.output.is_json() {
+            return print_json(&json!({ "pair": pair.to_string(), "tx_id": tx_id.to_string() }));
+        }
+
+        println!("✅ {} removed", pair);
+
+        Ok(())
+    }
+}
+
+/// Pairs supported by the oracle, read from its storage.
+pub(crate) fn supported_pairs(oracle: &Account) -> anyhow::Result<Vec<Pair>> {
+    let StorageSlot::Map(pairs) =
+        &oracle.storage().slots()[oracle_account_slot(PAIRS_MAP_SLOT) as usize]
+    else {
+        anyhow::bail!("The oracle storage doesn't contain a pair registry");
+    };
+    Ok(pairs
+        .entries()
+        .filter(|(_, pair_config)| pair_config[0] != ZERO)
+        .map(|(pair_word, _)| Pair::from(pair_word[3]))
+        .collect())
+}
+
+fn get_oracle_id(client_config: &ClientConfig) -> anyhow::Result<AccountId> {
+    let pragma_storage = JsonStorage::new(&client_config.accounts)?;
+    pragma_storage.oracle_id()
+}
+
+async fn list_pairs(
+    client: &mut Client<impl FeltRng>,
+    client_config: &ClientConfig,
+) -> anyhow::Result<()> {
+    sync_client(client).await?;
+
+    let oracle_id = get_oracle_id(client_config)?;
+    let oracle = get_account(client, oracle_id).await?;
+
+    let slots = oracle.storage().slots();
+    let (StorageSlot::Map(pairs), StorageSlot::Map(pair_publishers)) = (
+        &slots[oracle_account_slot(PAIRS_MAP_SLOT) as usize],
+        &slots[oracle_account_slot(PAIR_PUBLISHERS_MAP_SLOT) as usize],
+    ) else {
+        anyhow::bail!("The oracle storage doesn't contain a pair registry");
+    };
+
+    // (pair, decimals, publishers) of the supported pairs
+    let mut supported_pairs = Vec::new();
+    for (pair_word, pair_config) in pairs.entries() {
+        if pair_config[0] == ZERO {
+            continue;
+        }
+
+        let publishers = (0..pair_config[2].as_int() as u32)
+            .map(|i| {
+                let publisher_word =
+                    pair_publishers.get_value(&pair_publisher_key((*pair_word).into(), i));
+                Ok(PublisherId::from_word(publisher_word)?.to_string())
+            })
+            .collect::<anyhow::Result<Vec<String>>>()?;
+        supported_pairs.push((
+            Pair::from(pair_word[3]).to_string(),
+            pair_config[1].as_int(),
+            publishers,
+        ));
+    }
+
+    if client_config.output.is_json() {
+        let pairs: Vec<_> = supported_pairs
+            .iter()
+            .map(|(pair, decimals, publishers)| {
+                json!({ "pair": pair, "decimals": decimals, "publishers": publishers })
+            })
+            .collect();
+        return print_json(&json!({ "pairs": pairs }));
+    }
+
+    let mut table = Table::new();
+    table.set_format(*prettytable::format::consts::FORMAT_BOX_CHARS);
+    table.add_row(Row::new(vec![
+        Cell::new("Pair").style_spec("Fcb"),
+        Cell::new("Decimals").style_spec("Fcb"),
+        Cell::new("Publishers").style_spec("Fcb"),
+    ]));
+    for (pair, decimals, publishers) in supported_pairs.iter() {
+        table.add_row(Row::new(vec![
+            Cell::new(pair).style_spec("Fg"),
+            Cell::new(&decimals.to_string()).style_spec("Fw"),
+            Cell::new(&publishers.join("\n")).style_spec("Fy"),
+        ]));
+    }
+
+    println!(
+        "{}",
+        format!("📊 Supported pairs: {}\n", supported_pairs.len()).bright_yellow()
+    );
+    table.printstd();
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use miden_client::crypto::FeltRng;
+use miden_client::Client;
+use miden_client::Word;
+use miden_crypto::dsa::rpo_falcon512::PublicKey;
+use pm_accounts::oracle::GLOBAL_PAUSE_KEY;
+use pm_accounts::scripts::{pause_script, unpause_script};
+use pm_types::Pair;
+use pm_utils_cli::{parse_pair, parse_public_key, print_json, ClientConfig};
+use serde_json::json;
+
+use super::admins::submit_admin_script;
+
+#[derive(clap::Parser, Debug, Clone)]
+#[clap(about = "Pauses a pair, or the whole Oracle with --all")]
+pub struct PauseCmd {
+    // Input pair (format example: "BTC/USD")
+    #[clap(value_parser = parse_pair, required_unless_present = "all")]
+    pair: Option<Pair>,
+    /// Pauses every pair of the Oracle
+    #[clap(long, conflicts_with = "pair")]
+    all: bool,
+    /// Public key of an admin signing the transaction, required once an admin threshold is set.
+    /// Its secret key must be held by an account of the local store.
+    #[clap(long = "signer", value_parser = parse_public_key)]
+    signers: Vec<PublicKey>,
+}
+
+#[derive(clap::Parser, Debug, Clone)]
+#[clap(about = "Unpauses a pair, or the whole Oracle with --all")]
+pub struct UnpauseCmd {
+    // Input pair (format example: "BTC/USD")
+    #[clap(value_parser = parse_pair, required_unless_present = "all")]
+    pair: Option<Pair>,
+    /// Lifts the pause of the whole Oracle. Pairs paused one by one stay paused
+    #[clap(long, conflicts_with = "pair")]
+    all: bool,
+    /// Public key of an admin signing the transaction, required once an admin threshold is set.
+    /// Its secret key must be held by an account of the local store.
+    #[clap(long = "signer", value_parser = parse_public_key)]
+    signers: Vec<PublicKey>,
+}
+
+impl PauseCmd {
+    pub async fn call(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        client_config: &ClientConfig,
+    ) -> anyhow::Result<()> {
+        let tx_script_code = pause_script(pause_key(self.pair.as_ref(), self.all)?);
+        let tx_id =
+            submit_admin_script(client, client_config, tx_script_code, &self.signers).await?;
+
+        if client_config.output.is_json() {
+            return print_json(
+                &json!({ "pair": self.pair.as_ref().map(Pair::to_string), "tx_id": tx_id.to_string() }),
+            );
+        }
+
+        match &self.pair {
+            Some(pair) => println!("⏸️  {} paused", pair),
+            None => println!("⏸️  Oracle paused"),
+        }
+
+        Ok(())
+    }
+}
+
+impl UnpauseCmd {
+    pub async fn call(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        client_config: &ClientConfig,
+    ) -> anyhow::Result<()> {
+        let tx_script_code = unpause_script(pause_key(self.pair.as_ref(), self.all)?);
+        let tx_id =
+            submit_admin_script(client, client_config, tx_script_code, &self.signers).await?;
+
+        if client_config.output.is_json() {
+            return print_json(
+                &json!({ "pair": self.pair.as_ref().map(Pair::to_string), "tx_id": tx_id.to_string() }),
+            );
+        }
+
+        match &self.pair {
+            Some(pair) => println!("▶️  {} unpaused", pair),
+            None => println!("▶️  Oracle unpaused"),
+        }
+
+        Ok(())
+    }
+}
+
+/// Key of the paused map for the provided pair, or the global key with `--all`.
+/// Pausing the whole Oracle must be explicit, a forgotten pair must not stop every read.
+fn pause_key(pair: Option<&Pair>, all: bool) -> anyhow::Result<Word> {
+    match (pair, all) {
+        (Some(pair), false) => Ok(pair.to_word()),
+        (None, true) => Ok(GLOBAL_PAUSE_KEY),
+        _ => anyhow::bail!("Provide either a pair or --all"),
+    }
+}
@@ -1,30 +1,60 @@
 use anyhow::Context;
 use colored::*;
-use miden_client::{accounts::AccountId, crypto::FeltRng};
-use miden_client::{Client, ZERO};
-use pm_utils_cli::{JsonStorage, ORACLE_ACCOUNT_COLUMN, PRAGMA_ACCOUNTS_STORAGE_FILE};
+use miden_client::accounts::AccountStorage;
+use miden_client::crypto::FeltRng;
+use miden_client::{Client, Word, ZERO};
+use pm_accounts::oracle::{
+    oracle_account_slot, publisher_index_key, ORACLE_STORAGE_SIZE, PUBLISHERS_MAP_SLOT,
+    PUBLISHER_COUNT_SLOT, PUBLISHER_REGISTRY_SLOT,
+};
+use pm_types::PublisherId;
+use pm_utils_cli::{get_or_import_account, print_json, sync_client, ClientConfig, JsonStorage};
 use prettytable::{Cell, Row, Table};
+use serde_json::json;
+
+// Account storage indexes of the oracles storing their publishers in sequential value slots.
+// These oracles had an auth component before the oracle one, and many more storage slots.
+const LEGACY_NEXT_PUBLISHER_SLOT: u8 = 2;
+const LEGACY_PUBLISHER_REGISTRY_SLOT: u8 = 3;
+const LEGACY_FIRST_PUBLISHER_SLOT: u8 = 4;
 
 #[derive(clap::Parser, Debug, Clone)]
 #[clap(about = "Fetches the registered publishers")]
 pub struct PublishersCmd {}
 
 impl PublishersCmd {
-    pub async fn call(&self, client: &mut Client<impl FeltRng>) -> anyhow::Result<()> {
-        client.sync_state().await.unwrap();
-
-        let pragma_storage = JsonStorage::new(PRAGMA_ACCOUNTS_STORAGE_FILE)?;
-
-        let oracle_id = pragma_storage.get_key(ORACLE_ACCOUNT_COLUMN).unwrap();
-        let oracle_id = AccountId::from_hex(oracle_id).unwrap();
-        let (oracle, _) = client.get_account(oracle_id).await.unwrap();
-
-        // Retrieve the size of the storage
-        let publisher_count = oracle
-            .storage()
-            .get_item(2)
-            .context("Unable to retrieve publisher count")?[0]
-            .as_int();
+    pub async fn call(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        client_config: &ClientConfig,
+    ) -> anyhow::Result<()> {
+        sync_client(client).await?;
+
+        let pragma_storage = JsonStorage::new(&client_config.accounts)?;
+
+        let oracle_id = pragma_storage.oracle_id()?;
+        // Only the storage is read, so oracles without the current procedures are listed too
+        let oracle = get_or_import_account(client, oracle_id).await?;
+
+        let publishers = registered_publishers(oracle.storage())?;
+
+        if client_config.output.is_json() {
+            let publishers: Vec<_> = publishers
+                .iter()
+                .enumerate()
+                .map(|(i, (publisher_id, is_registered))| {
+                    json!({
+                        "index": i + 1,
+                        "publisher_id": publisher_id.to_string(),
+                        "active": is_registered,
+                    })
+                })
+                .collect();
+            return print_json(&json!({
+                "oracle_id": oracle_id.to_string(),
+                "publishers": publishers,
+            }));
+        }
 
         println!(
             "{}",
@@ -39,10 +69,10 @@ impl PublishersCmd {
         println!("{}", format!("🔍 Oracle ID: {}", oracle_id).bright_yellow());
         println!(
             "{}",
-            format!("📊 Total Publishers: {}\n", publisher_count - 3).bright_yellow()
+            format!("📊 Total Publishers: {}\n", publishers.len()).bright_yellow()
         );
 
-        if publisher_count <= 3 {
+        if publishers.is_empty() {
             println!(
                 "{}",
                 r#"
@@ -65,23 +95,16 @@ impl PublishersCmd {
         ]));
 
         // Add publisher rows
-        for i in 0..publisher_count - 3 {
-            let publisher_word = oracle
-                .storage()
-                .get_item((4 + i).try_into().context("Invalid publisher index")?)
-                .context("Failed to retrieve publisher details")?;
-
-            let publisher_id = publisher_word[3].as_int();
-
-            // Check if publisher is active
-            let status = oracle
-                .storage()
-                .get_map_item(3, [ZERO, ZERO, ZERO, publisher_word[3]])
-                .map_or("Inactive ❌", |_| "Active ✅");
+        for (i, (publisher_id, is_registered)) in publishers.iter().enumerate() {
+            let status = if *is_registered {
+                "Active ✅"
+            } else {
+                "Inactive ❌"
+            };
 
             table.add_row(Row::new(vec![
                 Cell::new(&format!("{}", i + 1)).style_spec("Fg"),
-                Cell::new(&format!("0x{:016x}", publisher_id)).style_spec("Fy"),
+                Cell::new(&publisher_id.to_string()).style_spec("Fy"),
                 Cell::new(status).style_spec("Fw"),
             ]));
         }
@@ -101,3 +124,110 @@ impl PublishersCmd {
         Ok(())
     }
 }
+
+/// Whether the oracle stores its publishers in sequential value slots, like the oracles created
+/// before the publishers map. These oracles have no pair registry.
+pub(crate) fn is_legacy_oracle(storage: &AccountStorage) -> bool {
+    storage.slots().len() > oracle_account_slot(ORACLE_STORAGE_SIZE) as usize
+}
+
+/// Reads the registered publishers of the oracle with their registration status.
+/// Oracles storing their publishers in sequential value slots are still supported.
+pub(crate) fn registered_publishers(
+    storage: &AccountStorage,
+) -> anyhow::Result<Vec<(PublisherId, bool)>> {
+    let mut publishers = Vec::new();
+
+    if is_legacy_oracle(storage) {
+        let next_publisher_slot = storage
+            .get_item(LEGACY_NEXT_PUBLISHER_SLOT)
+            .context("Unable to retrieve publisher count")?[0]
+            .as_int();
+        for slot in LEGACY_FIRST_PUBLISHER_SLOT as u64..=next_publisher_slot {
+            let publisher_word: Word = storage
+                .get_item(slot.try_into().context("Invalid publisher index")?)
+                .context("Failed to retrieve publisher details")?
+                .into();
+            let registration =
+                storage.get_map_item(LEGACY_PUBLISHER_REGISTRY_SLOT, publisher_word)?;
+            publishers.push((
+                PublisherId::from_word(publisher_word)?,
+                registration[0] != ZERO,
+            ));
+        }
+    } else {
+        let publisher_count = storage
+            .get_item(oracle_account_slot(PUBLISHER_COUNT_SLOT))
+            .context("Unable to retrieve publisher count")?[0]
+            .as_int();
+        for i in 0..publisher_count as u32 {
+            let publisher_word = storage
+                .get_map_item(
+                    oracle_account_slot(PUBLISHERS_MAP_SLOT),
+                    publisher_index_key(i).into(),
+                )
+                .context("Failed to retrieve publisher details")?;
+            let registration = storage
+                .get_map_item(oracle_account_slot(PUBLISHER_REGISTRY_SLOT), publisher_word)?;
+            publishers.push((
+                PublisherId::from_word(publisher_word)?,
+                registration[0] != ZERO,
+            ));
+        }
+    }
+
+    Ok(publishers)
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_client::accounts::{AccountId, StorageMap, StorageSlot};
+    use miden_client::crypto::RpoDigest;
+    use miden_client::Felt;
+
+    use super::*;
+
+    /// Storage of an oracle created before the publishers map: the auth key, then the oracle
+    /// component with the next publisher slot, the registry & a value slot per publisher.
+    fn legacy_oracle_storage(publishers: &[(PublisherId, bool)]) -> AccountStorage {
+        let mut slots = vec![
+            StorageSlot::Value([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)]),
+            StorageSlot::empty_map(),
+            StorageSlot::Value([
+                Felt::new(LEGACY_FIRST_PUBLISHER_SLOT as u64 + publishers.len() as u64 - 1),
+                ZERO,
+                ZERO,
+                ZERO,
+            ]),
+            StorageSlot::Map(
+                StorageMap::with_entries(publishers.iter().map(|(publisher_id, is_registered)| {
+                    (
+                        RpoDigest::new(publisher_id.to_word()),
+                        [Felt::new(*is_registered as u64), ZERO, ZERO, ZERO],
+                    )
+                }))
+                .unwrap(),
+            ),
+        ];
+        slots.extend(
+            publishers
+                .iter()
+                .map(|(publisher_id, _)| StorageSlot::Value(publisher_id.to_word())),
+        );
+        slots.resize_with(255, StorageSlot::empty_value);
+        AccountStorage::new(slots).unwrap()
+    }
+
+    #[test]
+    fn test_registered_publishers_of_legacy_oracle() {
+        let publishers: Vec<(PublisherId, bool)> =
+            [("0x800000000000001f", true), ("0x800000000000002f", false)]
+                .into_iter()
+                .map(|(id, is_registered)| (AccountId::from_hex(id).unwrap().into(), is_registered))
+                .collect();
+        let storage = legacy_oracle_storage(&publishers);
+
+        assert!(is_legacy_oracle(&storage));
+        assert_eq!(registered_publishers(&storage).unwrap(), publishers);
+    }
+}
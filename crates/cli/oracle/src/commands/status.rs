@@ -0,0 +1,274 @@
+use anyhow::Context;
+use colored::*;
+use miden_client::accounts::AccountId;
+use miden_client::crypto::FeltRng;
+use miden_client::Client;
+use pm_types::{median, Entry, Pair};
+use pm_utils_cli::{get_or_import_account, print_json, sync_client, ClientConfig, JsonStorage};
+use prettytable::{Cell, Row, Table};
+use serde_json::json;
+
+use super::median::{format_price, pair_entries};
+use super::pairs::supported_pairs;
+use super::publishers::{is_legacy_oracle, registered_publishers};
+use super::watch::{format_age, now, relative_change};
+
+/// Age, in seconds, after which an entry is flagged as stale by default.
+const DEFAULT_MAX_AGE: u64 = 300;
+
+/// Deviation from the median, in basis points, after which a price is flagged by default.
+const DEFAULT_MAX_DEVIATION_BPS: u64 = 100;
+
+#[derive(clap::Parser, Debug, Clone)]
+#[clap(
+    about = "Shows the latest price of every pair & publisher, with its deviation from the median"
+)]
+pub struct StatusCmd {
+    /// Age, in seconds, after which an entry is flagged as stale
+    #[clap(long, default_value_t = DEFAULT_MAX_AGE)]
+    max_age: u64,
+    /// Deviation from the median, in basis points, after which a price is flagged
+    #[clap(long, default_value_t = DEFAULT_MAX_DEVIATION_BPS)]
+    max_deviation_bps: u64,
+}
+
+/// Latest entry of a publisher for a pair, compared to the median of the pair.
+struct EntryStatus {
+    entry: Entry,
+    /// Move from the median, in percent.
+    deviation: f64,
+    stale: bool,
+    deviating: bool,
+}
+
+/// Status of a registered publisher for a pair.
+enum PublisherStatus {
+    /// The publisher isn't allowed to publish the pair.
+    NotAllowed,
+    NoEntry,
+    Entry(EntryStatus),
+}
+
+/// Status of the registered publishers of a pair, in registration order.
+struct PairStatus {
+    pair: Pair,
+    decimals: u32,
+    /// Median computed off-chain from the synced entries, the oracle isn't executed.
+    median: Option<u64>,
+    publishers: Vec<PublisherStatus>,
+}
+
+impl StatusCmd {
+    pub async fn call(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        client_config: &ClientConfig,
+    ) -> anyhow::Result<()> {
+        let pragma_storage = JsonStorage::new(&client_config.accounts)?;
+        let oracle_id = pragma_storage.oracle_id()?;
+
+        sync_client(client).await?;
+        // Only the storage is read, so oracles without the current procedures are shown too
+        let oracle = get_or_import_account(client, oracle_id).await?;
+
+        let publisher_ids: Vec<AccountId> = registered_publishers(oracle.storage())?
+            .into_iter()
+            .map(|(publisher_id, _)| publisher_id.account_id())
+            .collect();
+        let mut pairs = if is_legacy_oracle(oracle.storage()) {
+            vec![]
+        } else {
+            supported_pairs(&oracle)?
+        };
+        pairs.sort_by_key(|pair| pair.to_string());
+
+        let now = now();
+        let mut statuses = Vec::with_capacity(pairs.len());
+        for pair in pairs {
+            let pair_entries = pair_entries(client, &oracle, &pair)
+                .await
+                .with_context(|| format!("Could not read the entries of {pair}"))?;
+            let prices: Vec<u64> = pair_entries
+                .entries
+                .iter()
+                .map(|(_, entry)| entry.price)
+                .collect();
+            let median = median(&prices);
+
+            let publishers = publisher_ids
+                .iter()
+                .map(|publisher_id| {
+                    if !pair_entries.publishers.contains(publisher_id) {
+                        return PublisherStatus::NotAllowed;
+                    }
+                    match pair_entries
+                        .entries
+                        .iter()
+                        .find(|(id, _)| id == publisher_id)
+                    {
+                        Some((_, entry)) => {
+                            PublisherStatus::Entry(self.entry_status(entry.clone(), median, now))
+                        }
+                        None => PublisherStatus::NoEntry,
+                    }
+                })
+                .collect();
+
+            statuses.push(PairStatus {
+                pair,
+                decimals: pair_entries.decimals,
+                median,
+                publishers,
+            });
+        }
+
+        if client_config.output.is_json() {
+            return print_json(&self.status_json(oracle_id, &publisher_ids, &statuses, now));
+        }
+
+        println!(
+            "{}",
+            format!(
+                "📊 Oracle {}: {} pairs, {} publishers",
+                oracle_id,
+                statuses.len(),
+                publisher_ids.len()
+            )
+            .bright_yellow()
+        );
+        println!(
+            "{}",
+            format!(
+                "⚠️  flags entries older than {}s or further than {} bps from the median\n",
+                self.max_age, self.max_deviation_bps
+            )
+            .dimmed()
+        );
+
+        let registry = pragma_storage.publishers();
+        let mut header = vec![
+            Cell::new("Pair").style_spec("Fcb"),
+            Cell::new("Median (off-chain)").style_spec("Fcb"),
+        ];
+        header.extend(publisher_ids.iter().map(|publisher_id| {
+            // Publishers of the account registry are shown by name
+            let publisher_id = publisher_id.to_string();
+            let label = registry
+                .iter()
+                .find(|record| record.id == publisher_id)
+                .map(|record| format!("{}\n{}", record.name, publisher_id))
+                .unwrap_or(publisher_id);
+            Cell::new(&label).style_spec("Fcb")
+        }));
+
+        let mut table = Table::new();
+        table.set_format(*prettytable::format::consts::FORMAT_BOX_CHARS);
+        table.add_row(Row::new(header));
+
+        for status in &statuses {
+            let mut row = vec![
+                Cell::new(&status.pair.to_string()).style_spec("Fg"),
+                Cell::new(
+                    &status
+                        .median
+                        .map(|median| format_price(median, status.decimals))
+                        .unwrap_or_else(|| "-".to_string()),
+                )
+                .style_spec("Fy"),
+            ];
+            row.extend(status.publishers.iter().map(|publisher| match publisher {
+                PublisherStatus::NotAllowed => Cell::new(""),
+                PublisherStatus::NoEntry => Cell::new("no entry").style_spec("Fr"),
+                PublisherStatus::Entry(entry_status) => {
+                    let mut flags = Vec::new();
+                    if entry_status.stale {
+                        flags.push("⚠️ stale");
+                    }
+                    if entry_status.deviating {
+                        flags.push("⚠️ deviation");
+                    }
+                    let cell = Cell::new(&format!(
+                        "{}\n{:+.2}% · {}{}",
+                        format_price(entry_status.entry.price, status.decimals),
+                        entry_status.deviation,
+                        format_age(entry_status.entry.timestamp, now),
+                        flags
+                            .iter()
+                            .map(|flag| format!("\n{flag}"))
+                            .collect::<String>()
+                    ));
+                    if flags.is_empty() {
+                        cell
+                    } else {
+                        cell.style_spec("Fr")
+                    }
+                }
+            }));
+            table.add_row(Row::new(row));
+        }
+
+        table.printstd();
+
+        Ok(())
+    }
+
+    fn entry_status(&self, entry: Entry, median: Option<u64>, now: u64) -> EntryStatus {
+        let deviation = median
+            .map(|median| relative_change(median, entry.price))
+            .unwrap_or_default();
+        EntryStatus {
+            stale: now.saturating_sub(entry.timestamp) > self.max_age,
+            // The deviation is in percent, 1% being 100 bps
+            deviating: deviation.abs() * 100.0 > self.max_deviation_bps as f64,
+            deviation,
+            entry,
+        }
+    }
+
+    fn status_json(
+        &self,
+        oracle_id: AccountId,
+        publisher_ids: &[AccountId],
+        statuses: &[PairStatus],
+        now: u64,
+    ) -> serde_json::Value {
+        let pairs: Vec<_> = statuses
+            .iter()
+            .map(|status| {
+                let publishers: Vec<_> = publisher_ids
+                    .iter()
+                    .zip(&status.publishers)
+                    .filter_map(|(publisher_id, publisher)| {
+                        let entry = match publisher {
+                            PublisherStatus::NotAllowed => return None,
+                            PublisherStatus::NoEntry => None,
+                            PublisherStatus::Entry(status) => Some(json!({
+                                "price": status.entry.price,
+                                "timestamp": status.entry.timestamp,
+                                "age": now.saturating_sub(status.entry.timestamp),
+                                "deviation_bps": (status.deviation * 100.0).round() as i64,
+                                "stale": status.stale,
+                                "deviating": status.deviating,
+                            })),
+                        };
+                        Some(json!({ "publisher_id": publisher_id.to_string(), "entry": entry }))
+                    })
+                    .collect();
+                json!({
+                    "pair": status.pair.to_string(),
+                    "decimals": status.decimals,
+                    "median": status.median,
+                    "publishers": publishers,
+                })
+            })
+            .collect();
+
+        json!({
+            "oracle_id": oracle_id.to_string(),
+            "timestamp": now,
+            "max_age": self.max_age,
+            "max_deviation_bps": self.max_deviation_bps,
+            "pairs": pairs,
+        })
+    }
+}
@@ -0,0 +1,63 @@
+use colored::*;
+use miden_client::crypto::FeltRng;
+use miden_client::Client;
+use miden_crypto::dsa::rpo_falcon512::PublicKey;
+use pm_accounts::scripts::transfer_ownership_script;
+use pm_accounts::utils::public_key_to_hex;
+use pm_utils_cli::{local_public_keys, parse_public_key, print_json, ClientConfig};
+use serde_json::json;
+
+use super::admins::submit_admin_script;
+
+#[derive(clap::Parser, Debug, Clone)]
+#[clap(about = "Transfers the ownership of the Oracle to a new public key")]
+pub struct TransferOwnershipCmd {
+    /// Public key of the new owner
+    #[clap(value_parser = parse_public_key)]
+    new_owner: PublicKey,
+    /// Public key of an admin signing the transaction, required once an admin threshold is set.
+    /// Its secret key must be held by an account of the local store.
+    #[clap(long = "signer", value_parser = parse_public_key)]
+    signers: Vec<PublicKey>,
+}
+
+impl TransferOwnershipCmd {
+    pub async fn call(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        client_config: &ClientConfig,
+    ) -> anyhow::Result<()> {
+        let tx_script_code = transfer_ownership_script(self.new_owner);
+
+        let tx_id =
+            submit_admin_script(client, client_config, tx_script_code, &self.signers).await?;
+
+        let new_owner = public_key_to_hex(self.new_owner);
+        // The owner signs while the admin threshold is 0, which this store can only do with the
+        // secret key of the new owner
+        let is_local_owner = local_public_keys(client)
+            .await?
+            .contains(&self.new_owner.into());
+        if !is_local_owner {
+            eprintln!(
+                "{}",
+                format!(
+                    "⚠️  The secret key of {new_owner} is not in the local store, the oracle can't \
+                     be administered as its owner from this machine anymore"
+                )
+                .bright_yellow()
+            );
+        }
+
+        if client_config.output.is_json() {
+            return print_json(&json!({
+                "new_owner": new_owner,
+                "tx_id": tx_id.to_string(),
+            }));
+        }
+
+        println!("✅ Ownership transferred to {}", new_owner);
+
+        Ok(())
+    }
+}
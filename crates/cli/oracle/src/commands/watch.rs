@@ -0,0 +1,78 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use colored::*;
+use tokio::time::{Interval, MissedTickBehavior};
+
+/// Paces the refreshes of a `--watch` command until Ctrl+C is received.
+pub(crate) struct Watcher {
+    interval: Interval,
+    shutdown: Pin<Box<dyn Future<Output = std::io::Result<()>>>>,
+}
+
+impl Watcher {
+    pub(crate) fn new(period: Duration) -> Self {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Self {
+            interval,
+            shutdown: Box::pin(tokio::signal::ctrl_c()),
+        }
+    }
+
+    /// Waits for the next refresh, the first one being immediate. Returns false once Ctrl+C is
+    /// received.
+    pub(crate) async fn tick(&mut self) -> bool {
+        tokio::select! {
+            _ = &mut self.shutdown => false,
+            _ = self.interval.tick() => true,
+        }
+    }
+}
+
+/// Clears the terminal so the refreshed table replaces the previous one.
+pub(crate) fn clear_screen() {
+    print!("\x1B[2J\x1B[1;1H");
+}
+
+/// Current unix timestamp, in seconds.
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Arrow & relative move of a price compared to the previous refresh.
+pub(crate) fn change_indicator(previous: Option<u64>, current: u64) -> ColoredString {
+    match previous {
+        None => "new".normal(),
+        Some(previous) if current > previous => {
+            format!("▲ {:+.2}%", relative_change(previous, current)).bright_green()
+        }
+        Some(previous) if current < previous => {
+            format!("▼ {:+.2}%", relative_change(previous, current)).bright_red()
+        }
+        Some(_) => "=".dimmed(),
+    }
+}
+
+/// Move from `reference` to `price`, in percent.
+pub(crate) fn relative_change(reference: u64, price: u64) -> f64 {
+    if reference == 0 {
+        return 0.0;
+    }
+    (price as f64 - reference as f64) / reference as f64 * 100.0
+}
+
+/// Human readable age of a timestamp (example: "2m 5s").
+pub(crate) fn format_age(timestamp: u64, now: u64) -> String {
+    let age = now.saturating_sub(timestamp);
+    match age {
+        0..60 => format!("{age}s"),
+        60..3600 => format!("{}m {}s", age / 60, age % 60),
+        3600..86400 => format!("{}h {}m", age / 3600, age % 3600 / 60),
+        _ => format!("{}d {}h", age / 86400, age % 86400 / 3600),
+    }
+}
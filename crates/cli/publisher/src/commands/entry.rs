@@ -1,36 +1,45 @@
 use chrono::{DateTime, Utc};
-use miden_client::{accounts::AccountId, crypto::FeltRng, Client};
+use miden_client::{crypto::FeltRng, Client};
+use pm_accounts::publisher::{publisher_account_slot, ENTRIES_MAP_SLOT};
 use pm_types::{Entry, Pair};
-use pm_utils_cli::{JsonStorage, PRAGMA_ACCOUNTS_STORAGE_FILE, PUBLISHER_ACCOUNT_COLUMN};
+use pm_utils_cli::{
+    entry_json, get_account, parse_pair, print_json, sync_client, ClientConfig, JsonStorage,
+};
 use prettytable::{Cell, Row, Table};
-use std::str::FromStr;
 
 #[derive(clap::Parser, Debug, Clone)]
 #[clap(about = "Retrieve an entry for a given pair (published by this publisher)")]
 pub struct EntryCmd {
     // Input pair (format example: "BTC/USD")
-    pair: String,
+    #[clap(value_parser = parse_pair)]
+    pair: Pair,
+    /// Name or id of the publisher, the default publisher when omitted
+    #[clap(long)]
+    publisher: Option<String>,
 }
 
-const PUBLISHERS_ENTRIES_STORAGE_SLOT: u8 = 2;
-
 impl EntryCmd {
-    pub async fn call(&self, client: &mut Client<impl FeltRng>) -> anyhow::Result<()> {
-        client.sync_state().await.unwrap();
-        let pragma_storage = JsonStorage::new(PRAGMA_ACCOUNTS_STORAGE_FILE)?;
-        let publisher_id = pragma_storage.get_key(PUBLISHER_ACCOUNT_COLUMN).unwrap();
-        let publisher_id = AccountId::from_hex(publisher_id).unwrap();
+    pub async fn call(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        client_config: &ClientConfig,
+    ) -> anyhow::Result<()> {
+        sync_client(client).await?;
+        let pragma_storage = JsonStorage::new(&client_config.accounts)?;
+        let publisher_id = pragma_storage.publisher_id(self.publisher.as_deref())?;
 
-        let (publisher, _) = client.get_account(publisher_id).await.unwrap();
+        let publisher = get_account(client, publisher_id).await?;
 
-        let pair: Pair = Pair::from_str(&self.pair).unwrap();
-        // TODO: create a pair from str & a to_word
+        let pair = &self.pair;
         let entry = publisher
             .storage()
-            .get_map_item(PUBLISHERS_ENTRIES_STORAGE_SLOT, pair.to_word())
-            .unwrap();
+            .get_map_item(publisher_account_slot(ENTRIES_MAP_SLOT), pair.to_word())?;
         let entry = Entry::from(entry);
 
+        if client_config.output.is_json() {
+            return print_json(&entry_json(publisher_id, &entry));
+        }
+
         // Create the main info table
         let mut table = Table::new();
         table.set_format(*prettytable::format::consts::FORMAT_BOX_CHARS);
@@ -68,7 +77,8 @@ impl EntryCmd {
         ]));
 
         // Convert timestamp to human-readable format
-        let dt = DateTime::<Utc>::from_timestamp(entry.timestamp as i64, 0).unwrap();
+        let dt = DateTime::<Utc>::from_timestamp(entry.timestamp as i64, 0)
+            .ok_or_else(|| anyhow::anyhow!("Invalid entry timestamp {}", entry.timestamp))?;
         let formatted_time = dt.format("%Y-%m-%d %H:%M:%S UTC").to_string();
 
         table.add_row(Row::new(vec![
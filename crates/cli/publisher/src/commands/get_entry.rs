@@ -1,73 +1,56 @@
-use miden_client::transactions::{TransactionKernel, TransactionRequest, TransactionScript};
+use miden_client::crypto::FeltRng;
+use miden_client::transactions::TransactionRequest;
 use miden_client::Client;
-use miden_client::{accounts::AccountId, crypto::FeltRng};
-use pm_accounts::publisher::get_publisher_component_library;
-use pm_accounts::utils::word_to_masm;
+use pm_accounts::scripts::{compile_publisher_script, publisher_get_entry_script};
 use pm_types::Pair;
-use pm_utils_cli::{JsonStorage, PRAGMA_ACCOUNTS_STORAGE_FILE, PUBLISHER_ACCOUNT_COLUMN};
-use std::str::FromStr;
+use pm_utils_cli::{
+    execute_transaction, parse_pair, print_json, submit_transaction, ClientConfig, JsonStorage,
+};
+use serde_json::json;
 
 #[derive(clap::Parser, Debug, Clone)]
 #[clap(about = "Gets entry")]
 pub struct GetEntryCmd {
     // Input pair (format example: "BTC/USD")
-    pair: String,
+    #[clap(value_parser = parse_pair)]
+    pair: Pair,
+    /// Name or id of the publisher, the default publisher when omitted
+    #[clap(long)]
+    publisher: Option<String>,
 }
 
 impl GetEntryCmd {
-    pub async fn call(&self, client: &mut Client<impl FeltRng>) -> anyhow::Result<()> {
-        let pragma_storage = JsonStorage::new(PRAGMA_ACCOUNTS_STORAGE_FILE)?;
+    pub async fn call(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        client_config: &ClientConfig,
+    ) -> anyhow::Result<()> {
+        let pragma_storage = JsonStorage::new(&client_config.accounts)?;
 
-        let publisher_id = pragma_storage.get_key(PUBLISHER_ACCOUNT_COLUMN).unwrap();
-        let publisher_id = AccountId::from_hex(publisher_id).unwrap();
-
-        let pair: Pair = Pair::from_str(&self.pair).unwrap();
-        let tx_script_code = format!(
-            "
-            use.publisher_component::publisher_module
-            use.std::sys
-    
-            begin
-                push.{pair}
-
-                call.publisher_module::get_entry
-    
-                exec.sys::truncate_stack
-            end
-            ",
-            pair = word_to_masm(pair.to_word()),
-        );
+        let publisher_id = pragma_storage.publisher_id(self.publisher.as_deref())?;
 
+        let pair = &self.pair;
         // TODO: Can we pipe stdout to a variable so we can see the stack??
 
-        let get_entry_script = TransactionScript::compile(
-            tx_script_code,
-            [],
-            TransactionKernel::testing_assembler()
-                .with_debug_mode(true)
-                .with_library(get_publisher_component_library())
-                .map_err(|e| {
-                    anyhow::anyhow!("Error while setting up the component library: {e:?}")
-                })?
-                .clone(),
-        )
-        .map_err(|e| anyhow::anyhow!("Error while compiling the script: {e:?}"))?;
+        let get_entry_script =
+            compile_publisher_script(publisher_get_entry_script(pair.to_word()), vec![])?;
 
         let transaction_request = TransactionRequest::new()
             .with_custom_script(get_entry_script)
-            .unwrap()
+            .map_err(|e| anyhow::anyhow!("Error while building transaction request: {e:?}"))?
             .with_public_foreign_accounts([publisher_id])
-            .unwrap();
-
-        let tx_result = client
-            .new_transaction(publisher_id, transaction_request)
-            .await
-            .map_err(|e| anyhow::anyhow!("Error while creating a transaction: {e:?}"))?;
-
-        client
-            .submit_transaction(tx_result.clone())
-            .await
-            .map_err(|e| anyhow::anyhow!("Error while submitting a transaction: {e:?}"))?;
+            .map_err(|e| anyhow::anyhow!("Error while building transaction request: {e:?}"))?;
+
+        let tx_result = execute_transaction(client, publisher_id, transaction_request).await?;
+        let tx_id = submit_transaction(client, tx_result).await?;
+
+        if client_config.output.is_json() {
+            return print_json(&json!({
+                "publisher_id": publisher_id.to_string(),
+                "pair": pair.to_string(),
+                "tx_id": tx_id.to_string(),
+            }));
+        }
 
         Ok(())
     }
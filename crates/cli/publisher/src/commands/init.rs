@@ -1,36 +1,75 @@
+use std::path::PathBuf;
+
 use colored::*;
+use miden_client::accounts::AccountId;
 use miden_client::crypto::FeltRng;
 use miden_client::Client;
 use pm_accounts::publisher::PublisherAccountBuilder;
-use pm_utils_cli::{JsonStorage, PRAGMA_ACCOUNTS_STORAGE_FILE, PUBLISHER_ACCOUNT_COLUMN};
+use pm_utils_cli::{
+    import_oracle, parse_account_id, print_json, read_seed_file, sync_client, ClientConfig,
+    JsonStorage,
+};
+use serde_json::json;
 
 #[derive(clap::Parser, Debug, Clone)]
 #[clap(about = "Creates a new Publisher Account")]
 pub struct InitCmd {
-    // TODO: We may want to create ONLY a publisher. And assume the Oracle was created by someone else.
-    // In this case, just store the oracle id in the storage.
-    // If not provided and the oracle_id is empty in the storage, error!
-    oracle_id: Option<String>,
+    /// Id of an Oracle created elsewhere, tracked & stored in the account registry
+    #[clap(long, value_parser = parse_account_id)]
+    oracle: Option<AccountId>,
+    /// Name of the publisher in the account registry, "publisher-<N>" by default
+    #[clap(long)]
+    name: Option<String>,
+    /// File holding a hex encoded 32 bytes seed, the same seed always creates the same
+    /// Publisher id. The seed is secret: it derives the publisher Falcon key, keep the file
+    /// private
+    #[clap(long)]
+    seed_file: Option<PathBuf>,
 }
 
 impl InitCmd {
-    pub async fn call(&self, client: &mut Client<impl FeltRng>) -> anyhow::Result<()> {
-        // TODO: Refine this condition & logic
-        // if JsonStorage::exists(PRAGMA_ACCOUNTS_STORAGE) && JsonStorage::new(PRAGMA_ACCOUNTS_STORAGE).get_key(PUBLISHER_ACCOUNT_ID).is_some() {
-        //     bail!("A Publisher has already been created! Delete it if you wanna start over.");
-        // }
-        client.sync_state().await.unwrap();
-
-        // TODO: Check that an oracle id has been provided or that it exists in the storage.
-
-        let (publisher_account, _) = PublisherAccountBuilder::new()
-            .with_client(client)
-            .build()
-            .await;
+    pub async fn call(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        client_config: &ClientConfig,
+    ) -> anyhow::Result<()> {
+        let mut pragma_storage = JsonStorage::new(&client_config.accounts)?;
+        let name = match &self.name {
+            Some(name) => name.clone(),
+            None => format!("publisher-{}", pragma_storage.publishers().len() + 1),
+        };
+        if pragma_storage
+            .publishers()
+            .iter()
+            .any(|publisher| publisher.name == name)
+        {
+            anyhow::bail!("A publisher named {name} already exists");
+        }
+
+        sync_client(client).await?;
+
+        if let Some(oracle_id) = self.oracle {
+            import_oracle(client, oracle_id).await?;
+            pragma_storage.set_oracle_id(oracle_id)?;
+        }
+
+        let mut builder = PublisherAccountBuilder::new();
+        if let Some(seed_file) = &self.seed_file {
+            builder = builder.with_seed(read_seed_file(seed_file)?);
+        }
+        let (publisher_account, _) = builder.with_client(client).build().await?;
         let created_publisher_id = publisher_account.id();
 
-        let mut pragma_storage = JsonStorage::new(PRAGMA_ACCOUNTS_STORAGE_FILE)?;
-        pragma_storage.add_key(PUBLISHER_ACCOUNT_COLUMN, &created_publisher_id.to_string())?;
+        pragma_storage.add_publisher(&name, created_publisher_id)?;
+
+        if client_config.output.is_json() {
+            return print_json(&json!({
+                "name": name,
+                "publisher_id": created_publisher_id.to_string(),
+                "oracle_id": pragma_storage.oracle_id().ok().map(|oracle_id| oracle_id.to_string()),
+                "accounts": client_config.accounts,
+            }));
+        }
 
         // Clear screen for better presentation
         print!("\x1B[2J\x1B[1;1H");
@@ -67,11 +106,19 @@ impl InitCmd {
             format!(
                 "
                 ╭────────────────────────────────────────────────────────────╮
+                │ Name: {}
                 │ ID: {}
+                │ Oracle: {}
                 │ Storage: {}
                 ╰────────────────────────────────────────────────────────────╯",
+                name.bright_white(),
                 created_publisher_id.to_string().bright_white(),
-                PRAGMA_ACCOUNTS_STORAGE_FILE.bright_white()
+                pragma_storage
+                    .oracle_id()
+                    .map(|oracle_id| oracle_id.to_string())
+                    .unwrap_or_else(|_| "-".to_string())
+                    .bright_white(),
+                client_config.accounts.display().to_string().bright_white()
             )
             .bright_blue()
         );
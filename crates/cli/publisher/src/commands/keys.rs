@@ -0,0 +1,99 @@
+use std::path::PathBuf;
+
+use miden_client::crypto::FeltRng;
+use miden_client::Client;
+use pm_utils_cli::{
+    export_account_keys, import_account_keys, print_json, read_key_password, ClientConfig,
+    JsonStorage,
+};
+use serde_json::json;
+
+#[derive(clap::Parser, Debug, Clone)]
+#[clap(about = "Exports or imports a Publisher account with its key")]
+pub struct KeysCmd {
+    #[command(subcommand)]
+    action: KeysAction,
+}
+
+#[derive(clap::Subcommand, Debug, Clone)]
+enum KeysAction {
+    /// Writes a Publisher account & its secret key to a password-encrypted file
+    Export {
+        #[clap(long, default_value = "publisher.key")]
+        file: PathBuf,
+        /// Name or id of the publisher, the default publisher when omitted
+        #[clap(long)]
+        publisher: Option<String>,
+    },
+    /// Restores a Publisher account & its secret key from an encrypted file
+    Import {
+        file: PathBuf,
+        /// Name of the publisher in the account registry, "publisher-<N>" by default
+        #[clap(long)]
+        name: Option<String>,
+    },
+}
+
+impl KeysCmd {
+    pub async fn call(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        client_config: &ClientConfig,
+    ) -> anyhow::Result<()> {
+        let mut pragma_storage = JsonStorage::new(&client_config.accounts)?;
+
+        match &self.action {
+            KeysAction::Export { file, publisher } => {
+                let publisher_id = pragma_storage.publisher_id(publisher.as_deref())?;
+                let password = read_key_password(true)?;
+                export_account_keys(client, publisher_id, file, &password).await?;
+
+                if client_config.output.is_json() {
+                    return print_json(&json!({
+                        "publisher_id": publisher_id.to_string(),
+                        "file": file,
+                    }));
+                }
+
+                println!(
+                    "✅ Publisher {} exported to {}",
+                    publisher_id,
+                    file.display()
+                );
+            }
+            KeysAction::Import { file, name } => {
+                let name = match name {
+                    Some(name) => name.clone(),
+                    None => format!("publisher-{}", pragma_storage.publishers().len() + 1),
+                };
+                if pragma_storage
+                    .publishers()
+                    .iter()
+                    .any(|publisher| publisher.name == name)
+                {
+                    anyhow::bail!("A publisher named {name} already exists");
+                }
+                let password = read_key_password(false)?;
+                let publisher = import_account_keys(client, file, &password).await?;
+                pragma_storage.add_publisher(&name, publisher.id())?;
+
+                if client_config.output.is_json() {
+                    return print_json(&json!({
+                        "name": name,
+                        "publisher_id": publisher.id().to_string(),
+                        "file": file,
+                    }));
+                }
+
+                println!(
+                    "✅ Publisher {} imported from {} as {}",
+                    publisher.id(),
+                    file.display(),
+                    name
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
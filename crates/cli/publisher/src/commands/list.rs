@@ -0,0 +1,62 @@
+use colored::*;
+use pm_utils_cli::{print_json, ClientConfig, JsonStorage};
+use prettytable::{Cell, Row, Table};
+use serde_json::json;
+
+#[derive(clap::Parser, Debug, Clone)]
+#[clap(about = "Lists the publishers of the account registry")]
+pub struct ListCmd {}
+
+impl ListCmd {
+    pub fn call(&self, client_config: &ClientConfig) -> anyhow::Result<()> {
+        let pragma_storage = JsonStorage::new(&client_config.accounts)?;
+        let publishers = pragma_storage.publishers();
+
+        if client_config.output.is_json() {
+            let publishers: Vec<_> = publishers
+                .iter()
+                .map(|publisher| {
+                    json!({
+                        "name": publisher.name,
+                        "publisher_id": publisher.id,
+                        "default": pragma_storage.default_publisher() == Some(publisher.id.as_str()),
+                    })
+                })
+                .collect();
+            return print_json(&json!({ "publishers": publishers }));
+        }
+
+        if publishers.is_empty() {
+            println!(
+                "{}",
+                "ℹ️  No publisher yet! Use 'pm-publisher-cli init --name [NAME]' to create one."
+                    .bright_yellow()
+            );
+            return Ok(());
+        }
+
+        let mut table = Table::new();
+        table.set_format(*prettytable::format::consts::FORMAT_BOX_CHARS);
+        table.add_row(Row::new(vec![
+            Cell::new("Name").style_spec("Fcb"),
+            Cell::new("Publisher ID").style_spec("Fcb"),
+            Cell::new("Default").style_spec("Fcb"),
+        ]));
+        for publisher in publishers.iter() {
+            let is_default = pragma_storage.default_publisher() == Some(publisher.id.as_str());
+            table.add_row(Row::new(vec![
+                Cell::new(&publisher.name).style_spec("Fg"),
+                Cell::new(&publisher.id).style_spec("Fy"),
+                Cell::new(if is_default { "✅" } else { "" }).style_spec("Fw"),
+            ]));
+        }
+
+        println!(
+            "{}",
+            format!("📋 Publishers: {}\n", publishers.len()).bright_yellow()
+        );
+        table.printstd();
+
+        Ok(())
+    }
+}
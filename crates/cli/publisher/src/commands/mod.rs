@@ -1,15 +1,23 @@
 pub mod entry;
 pub mod get_entry;
 pub mod init;
+pub mod keys;
+pub mod list;
 pub mod publish;
+pub mod publish_batch;
+pub mod run;
 pub mod sync;
 
 use clap::Parser;
 use entry::EntryCmd;
 use get_entry::GetEntryCmd;
 use init::InitCmd;
-use pm_utils_cli::setup_client;
+use keys::KeysCmd;
+use list::ListCmd;
+use pm_utils_cli::{setup_client, ClientConfig};
 use publish::PublishCmd;
+use publish_batch::PublishBatchCmd;
+use run::RunCmd;
 use sync::SyncCmd;
 
 #[derive(Debug, Parser, Clone)]
@@ -17,9 +25,21 @@ pub enum SubCommand {
     // Init a publisher configuration
     #[clap(name = "init", bin_name = "init")]
     Init(InitCmd),
+    // Export or import an account with its key
+    #[clap(name = "keys", bin_name = "keys")]
+    Keys(KeysCmd),
     // Publish an entry
     #[clap(name = "publish", bin_name = "publish")]
     Publish(PublishCmd),
+    // Publish many entries in a single transaction
+    #[clap(name = "publish-batch", bin_name = "publish-batch")]
+    PublishBatch(PublishBatchCmd),
+    // List the publishers of the account registry
+    #[clap(name = "list", bin_name = "list")]
+    List(ListCmd),
+    // Run a price feeder publishing the prices of its sources
+    #[clap(name = "run", bin_name = "run")]
+    Run(RunCmd),
     // Get an entry for a given pair id
     #[clap(name = "get", bin_name = "get")]
     Entry(EntryCmd),
@@ -32,15 +52,19 @@ pub enum SubCommand {
 }
 
 impl SubCommand {
-    pub async fn call(&self) -> anyhow::Result<()> {
-        let mut client = setup_client().await;
+    pub async fn call(&self, client_config: &ClientConfig) -> anyhow::Result<()> {
+        let mut client = setup_client(client_config).await?;
 
         match self {
-            Self::Init(cmd) => cmd.call(&mut client).await?,
-            Self::Publish(cmd) => cmd.call(&mut client).await?,
-            Self::Entry(cmd) => cmd.call(&mut client).await?,
-            Self::Sync(cmd) => cmd.call(&mut client).await?,
-            Self::Get(cmd) => cmd.call(&mut client).await?,
+            Self::Init(cmd) => cmd.call(&mut client, client_config).await?,
+            Self::Keys(cmd) => cmd.call(&mut client, client_config).await?,
+            Self::Publish(cmd) => cmd.call(&mut client, client_config).await?,
+            Self::PublishBatch(cmd) => cmd.call(&mut client, client_config).await?,
+            Self::List(cmd) => cmd.call(client_config)?,
+            Self::Run(cmd) => cmd.call(&mut client, client_config).await?,
+            Self::Entry(cmd) => cmd.call(&mut client, client_config).await?,
+            Self::Sync(cmd) => cmd.call(&mut client, client_config).await?,
+            Self::Get(cmd) => cmd.call(&mut client, client_config).await?,
         };
 
         Ok(())
@@ -1,91 +1,76 @@
-use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use miden_client::{
-    accounts::AccountId,
-    crypto::FeltRng,
-    transactions::{TransactionKernel, TransactionRequest, TransactionScript},
-    Client, Word,
-};
+use miden_client::{crypto::FeltRng, transactions::TransactionRequest, Client};
 
-use pm_accounts::{publisher::get_publisher_component_library, utils::word_to_masm};
+use pm_accounts::scripts::{compile_publisher_script, publish_entry_script};
 use pm_types::{Entry, Pair};
-use pm_utils_cli::{JsonStorage, PRAGMA_ACCOUNTS_STORAGE_FILE, PUBLISHER_ACCOUNT_COLUMN};
+use pm_utils_cli::{
+    entry_json, execute_transaction, parse_pair, print_json, submit_transaction, ClientConfig,
+    JsonStorage,
+};
+
+/// How far ahead of the local clock an entry timestamp can be, in seconds.
+pub(crate) const MAX_TIMESTAMP_DRIFT: u64 = 60;
 
 #[derive(clap::Parser, Debug, Clone)]
 #[clap(about = "Publish an entry(Callable by the publisher itself)")]
 pub struct PublishCmd {
-    pair: String, //"BTC/USD"
+    #[clap(value_parser = parse_pair)]
+    pair: Pair, //"BTC/USD"
     price: u64,
     decimals: u32,
     timestamp: u64,
+    /// Name or id of the publisher, the default publisher when omitted
+    #[clap(long)]
+    publisher: Option<String>,
 }
 
 impl PublishCmd {
-    pub async fn call(&self, client: &mut Client<impl FeltRng>) -> anyhow::Result<()> {
-        let pragma_storage = JsonStorage::new(PRAGMA_ACCOUNTS_STORAGE_FILE)?;
-        let publisher_id = pragma_storage.get_key(PUBLISHER_ACCOUNT_COLUMN).unwrap();
-        let publisher_id = AccountId::from_hex(publisher_id).unwrap();
+    pub async fn call(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        client_config: &ClientConfig,
+    ) -> anyhow::Result<()> {
+        let pragma_storage = JsonStorage::new(&client_config.accounts)?;
+        let publisher_id = pragma_storage.publisher_id(self.publisher.as_deref())?;
 
-        let pair: Pair = Pair::from_str(&self.pair).unwrap();
+        check_timestamp(self.timestamp)?;
 
-        let entry: Entry = Entry {
-            pair: pair.clone(),
+        let entry = Entry {
+            pair: self.pair.clone(),
             price: self.price,
             decimals: self.decimals,
             timestamp: self.timestamp,
         };
-
-        let entry_as_word: Word = entry.try_into().unwrap();
-        let pair_as_word: Word = pair.to_word();
-        let tx_script_code = format!(
-            "
-                use.publisher_component::publisher_module
-                use.std::sys
-        
-                begin
-                    push.{entry}
-                    push.{pair}
-
-                    call.publisher_module::publish_entry
-        
-                    dropw
-        
-                    call.::miden::contracts::auth::basic::auth_tx_rpo_falcon512
-                    exec.sys::truncate_stack
-                end
-                ",
-            pair = word_to_masm(pair_as_word),
-            entry = word_to_masm(entry_as_word)
-        );
-        let publish_script = TransactionScript::compile(
-            tx_script_code,
-            [],
-            TransactionKernel::testing_assembler()
-                .with_debug_mode(true)
-                .with_library(get_publisher_component_library())
-                .map_err(|e| {
-                    anyhow::anyhow!("Error while setting up the component library: {e:?}")
-                })?
-                .clone(),
-        )
-        .map_err(|e| anyhow::anyhow!("Error while compiling the script: {e:?}"))?;
+        let publish_script = compile_publisher_script(publish_entry_script(&entry)?, vec![])?;
 
         let transaction_request = TransactionRequest::new()
             .with_custom_script(publish_script)
             .map_err(|e| anyhow::anyhow!("Error while building transaction request: {e:?}"))?;
 
-        let transaction = client
-            .new_transaction(publisher_id, transaction_request)
-            .await
-            .map_err(|e| anyhow::anyhow!("Error while creating a transaction: {e:?}"))?;
+        let transaction = execute_transaction(client, publisher_id, transaction_request).await?;
+        let tx_id = submit_transaction(client, transaction).await?;
 
-        client
-            .submit_transaction(transaction.clone())
-            .await
-            .map_err(|e| anyhow::anyhow!("Error while submitting a transaction: {e:?}"))?;
+        if client_config.output.is_json() {
+            let mut result = entry_json(publisher_id, &entry);
+            result["tx_id"] = tx_id.to_string().into();
+            return print_json(&result);
+        }
 
         println!(" Publish successful!");
 
         Ok(())
     }
 }
+
+/// Rejects timestamps too far in the future. The publisher account only checks that entries
+/// are more recent than the stored ones, so a wrong clock would block the next updates.
+pub(crate) fn check_timestamp(timestamp: u64) -> anyhow::Result<()> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    if timestamp > now + MAX_TIMESTAMP_DRIFT {
+        anyhow::bail!(
+            "Timestamp {timestamp} is more than {MAX_TIMESTAMP_DRIFT}s ahead of the current time ({now})"
+        );
+    }
+    Ok(())
+}
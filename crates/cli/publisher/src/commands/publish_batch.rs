@@ -0,0 +1,156 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::Context;
+use miden_client::{
+    accounts::AccountId,
+    crypto::FeltRng,
+    transactions::{TransactionId, TransactionRequest},
+    Client,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use pm_accounts::{
+    publisher::entries_advice,
+    scripts::{compile_publisher_script, publish_entries_script},
+};
+use pm_types::{Entry, Pair};
+use pm_utils_cli::{
+    entry_json, execute_transaction, print_json, submit_transaction, ClientConfig, JsonStorage,
+};
+
+use super::publish::check_timestamp;
+
+#[derive(clap::Parser, Debug, Clone)]
+#[clap(about = "Publish many entries in a single transaction (Callable by the publisher itself)")]
+pub struct PublishBatchCmd {
+    /// CSV (pair,price,decimals,timestamp) or JSON file containing the entries
+    file: PathBuf,
+    /// Name or id of the publisher, the default publisher when omitted
+    #[clap(long)]
+    publisher: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchEntry {
+    pair: String,
+    price: u64,
+    decimals: u32,
+    timestamp: u64,
+}
+
+impl TryFrom<BatchEntry> for Entry {
+    type Error = anyhow::Error;
+
+    fn try_from(entry: BatchEntry) -> anyhow::Result<Self> {
+        Ok(Entry {
+            pair: Pair::from_str(&entry.pair)
+                .with_context(|| format!("Invalid pair {}", entry.pair))?,
+            price: entry.price,
+            decimals: entry.decimals,
+            timestamp: entry.timestamp,
+        })
+    }
+}
+
+impl PublishBatchCmd {
+    pub async fn call(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        client_config: &ClientConfig,
+    ) -> anyhow::Result<()> {
+        let pragma_storage = JsonStorage::new(&client_config.accounts)?;
+        let publisher_id = pragma_storage.publisher_id(self.publisher.as_deref())?;
+
+        let entries = read_entries(&self.file)?;
+        if entries.is_empty() {
+            anyhow::bail!("No entry found in {}", self.file.display());
+        }
+        for entry in &entries {
+            check_timestamp(entry.timestamp)
+                .with_context(|| format!("Invalid entry for {}", entry.pair))?;
+        }
+        let tx_id = submit_entries(client, publisher_id, &entries).await?;
+
+        if client_config.output.is_json() {
+            let entries: Vec<_> = entries
+                .iter()
+                .map(|entry| entry_json(publisher_id, entry))
+                .collect();
+            return print_json(&json!({ "tx_id": tx_id.to_string(), "entries": entries }));
+        }
+
+        println!("✅ Published {} entries!", entries.len());
+
+        Ok(())
+    }
+}
+
+/// Publishes the entries in a single transaction of the publisher account, returns the id of
+/// the transaction.
+pub(crate) async fn submit_entries(
+    client: &mut Client<impl FeltRng>,
+    publisher_id: AccountId,
+    entries: &[Entry],
+) -> anyhow::Result<TransactionId> {
+    let (entries_key, entries_values) = entries_advice(entries)?;
+
+    let publish_script = compile_publisher_script(
+        publish_entries_script(entries_key, entries.len()),
+        vec![(entries_key.into(), entries_values)],
+    )?;
+
+    let transaction_request = TransactionRequest::new()
+        .with_custom_script(publish_script)
+        .map_err(|e| anyhow::anyhow!("Error while building transaction request: {e:?}"))?;
+
+    let transaction = execute_transaction(client, publisher_id, transaction_request).await?;
+    let tx_id = submit_transaction(client, transaction).await?;
+
+    Ok(tx_id)
+}
+
+/// Reads the entries of a JSON file (array of objects) or of a CSV file
+/// (`pair,price,decimals,timestamp` lines, with an optional header).
+pub(crate) fn read_entries(path: &Path) -> anyhow::Result<Vec<Entry>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Could not read the entries file {}", path.display()))?;
+
+    let entries: Vec<BatchEntry> = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => serde_json::from_str(&content).context("Invalid JSON entries file")?,
+        Some("csv") => content
+            .lines()
+            .map(str::trim)
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .filter(|(i, line)| !(*i == 0 && line.starts_with("pair")))
+            .map(|(i, line)| {
+                parse_csv_line(line).with_context(|| format!("Invalid line {}", i + 1))
+            })
+            .collect::<anyhow::Result<_>>()?,
+        _ => anyhow::bail!(
+            "Unsupported entries format for {}, expected a .csv or .json file",
+            path.display()
+        ),
+    };
+
+    entries.into_iter().map(Entry::try_from).collect()
+}
+
+fn parse_csv_line(line: &str) -> anyhow::Result<BatchEntry> {
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    let [pair, price, decimals, timestamp] = fields[..] else {
+        anyhow::bail!(
+            "Expected 4 fields (pair,price,decimals,timestamp), got {}",
+            fields.len()
+        );
+    };
+    Ok(BatchEntry {
+        pair: pair.to_string(),
+        price: price.parse().context("Invalid price")?,
+        decimals: decimals.parse().context("Invalid decimals")?,
+        timestamp: timestamp.parse().context("Invalid timestamp")?,
+    })
+}
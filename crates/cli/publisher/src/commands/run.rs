@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use miden_client::{crypto::FeltRng, Client};
+
+use pm_utils_cli::{ClientConfig, JsonStorage};
+
+use crate::feeder::{Feeder, FeederConfig};
+
+#[derive(clap::Parser, Debug, Clone)]
+#[clap(about = "Runs a price feeder publishing the prices of the configured sources")]
+pub struct RunCmd {
+    /// TOML config of the feeder (pairs, sources, deviation & heartbeat policy)
+    #[clap(long)]
+    config: PathBuf,
+    /// Name or id of the publisher, the default publisher when omitted
+    #[clap(long)]
+    publisher: Option<String>,
+}
+
+impl RunCmd {
+    pub async fn call(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        client_config: &ClientConfig,
+    ) -> anyhow::Result<()> {
+        let pragma_storage = JsonStorage::new(&client_config.accounts)?;
+        let publisher_id = pragma_storage.publisher_id(self.publisher.as_deref())?;
+
+        let config = FeederConfig::from_file(&self.config)?;
+        let mut feeder = Feeder::new(config, publisher_id)?.with_output(client_config.output);
+        feeder.run(client).await
+    }
+}
@@ -1,17 +1,31 @@
 use clap::Parser;
 
 use miden_client::{crypto::FeltRng, Client};
+use pm_utils_cli::{print_json, sync_client, ClientConfig};
+use serde_json::json;
 
 #[derive(Debug, Clone, Parser)]
 #[clap(about = "Sync local state with the blockchain")]
 pub struct SyncCmd {}
 
 impl SyncCmd {
-    pub async fn call(&self, client: &mut Client<impl FeltRng>) -> anyhow::Result<()> {
-        let new_details = client
-            .sync_state()
-            .await
-            .map_err(|e| anyhow::anyhow!("Could not sync state: {}", e.to_string()))?;
+    pub async fn call(
+        &self,
+        client: &mut Client<impl FeltRng>,
+        client_config: &ClientConfig,
+    ) -> anyhow::Result<()> {
+        let new_details = sync_client(client).await?;
+        if client_config.output.is_json() {
+            return print_json(&json!({
+                "block_num": new_details.block_num,
+                "received_notes": new_details.received_notes.len(),
+                "committed_notes": new_details.committed_notes.len(),
+                "consumed_notes": new_details.consumed_notes.len(),
+                "updated_accounts": new_details.updated_accounts.len(),
+                "committed_transactions": new_details.committed_transactions.len(),
+            }));
+        }
+
         println!("🔁 Sync successful!\n");
 
         println!("State synced to block {}", new_details.block_num);
@@ -0,0 +1,93 @@
+use anyhow::Context;
+
+use pm_types::{median, Entry};
+
+/// Rules used to combine the prices of several sources into a single entry.
+#[derive(Debug, Clone, Copy)]
+pub struct AggregationPolicy {
+    /// Minimum number of sources that must agree on a price for it to be published.
+    pub min_sources: usize,
+    /// Prices further than this from the median of all the sources are rejected, in basis
+    /// points. Disabled while 0.
+    pub outlier_bps: u64,
+}
+
+/// Price of a pair combined from several sources.
+#[derive(Debug, Clone)]
+pub struct AggregatedEntry {
+    pub entry: Entry,
+    /// Sources whose price was used in the median.
+    pub sources: Vec<String>,
+    /// Sources whose price was rejected as an outlier.
+    pub rejected: Vec<String>,
+}
+
+impl AggregationPolicy {
+    /// Combines the entries of a single pair, given with the name of their source.
+    /// The prices are converted to the highest decimals before taking their median, the
+    /// timestamp is the one of the most recent entry kept.
+    pub fn aggregate(&self, entries: Vec<(String, Entry)>) -> anyhow::Result<AggregatedEntry> {
+        let Some((_, first)) = entries.first() else {
+            anyhow::bail!("No price to aggregate");
+        };
+        let pair = first.pair.clone();
+        let decimals = entries
+            .iter()
+            .map(|(_, entry)| entry.decimals)
+            .max()
+            .unwrap_or_default();
+
+        let prices = entries
+            .iter()
+            .map(|(source, entry)| {
+                let factor = 10_u128
+                    .checked_pow(decimals - entry.decimals)
+                    .context("Too many decimals")?;
+                let price = u64::try_from(entry.price as u128 * factor).with_context(|| {
+                    format!("Price of {source} doesn't fit in {decimals} decimals")
+                })?;
+                Ok(price)
+            })
+            .collect::<anyhow::Result<Vec<u64>>>()?;
+
+        let reference = median(&prices).context("No price to aggregate")?;
+        let (kept, rejected): (Vec<usize>, Vec<usize>) = (0..entries.len()).partition(|&i| {
+            self.outlier_bps == 0 || deviation_bps(reference, prices[i]) <= self.outlier_bps as u128
+        });
+
+        if kept.len() < self.min_sources {
+            anyhow::bail!(
+                "Only {} sources agree on {} ({} required)",
+                kept.len(),
+                pair,
+                self.min_sources
+            );
+        }
+
+        let kept_prices: Vec<u64> = kept.iter().map(|&i| prices[i]).collect();
+        let timestamp = kept
+            .iter()
+            .map(|&i| entries[i].1.timestamp)
+            .max()
+            .unwrap_or_default();
+
+        Ok(AggregatedEntry {
+            entry: Entry {
+                pair,
+                price: median(&kept_prices).context("No price left to aggregate")?,
+                decimals,
+                timestamp,
+            },
+            sources: kept.iter().map(|&i| entries[i].0.clone()).collect(),
+            rejected: rejected.iter().map(|&i| entries[i].0.clone()).collect(),
+        })
+    }
+}
+
+/// Relative distance of a price to a reference price, in basis points.
+pub(crate) fn deviation_bps(reference: u64, price: u64) -> u128 {
+    if reference == 0 {
+        return u128::MAX;
+    }
+    reference.abs_diff(price) as u128 * 10_000 / reference as u128
+}
@@ -0,0 +1,224 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use pm_accounts::oracle::DEFAULT_PAIR_DECIMALS;
+use pm_types::Pair;
+
+fn default_interval() -> u64 {
+    10
+}
+
+fn default_heartbeat() -> u64 {
+    300
+}
+
+fn default_deviation_bps() -> u64 {
+    50
+}
+
+fn default_max_batch_size() -> usize {
+    16
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+fn default_retry_delay() -> u64 {
+    2
+}
+
+fn default_min_sources() -> usize {
+    1
+}
+
+fn default_outlier_bps() -> u64 {
+    500
+}
+
+fn default_source_decimals() -> u32 {
+    DEFAULT_PAIR_DECIMALS
+}
+
+fn default_timeout() -> u64 {
+    5
+}
+
+/// A source the feeder pulls prices from.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SourceConfig {
+    /// CSV (`pair,price,decimals,timestamp`) or JSON file, read again at every fetch.
+    File {
+        path: PathBuf,
+    },
+    /// Recorded file in the same format, replaying one price per pair at every fetch.
+    Replay {
+        path: PathBuf,
+        /// Starts again from the first recorded price once all were replayed.
+        #[serde(default)]
+        repeat: bool,
+    },
+    Binance(ExchangeConfig),
+    Coinbase(ExchangeConfig),
+    Kraken(ExchangeConfig),
+    /// Fixed decimal prices by pair, to run the feeder offline.
+    Mock {
+        /// Name of the source in the logs & metrics, "mock" by default.
+        #[serde(default)]
+        name: Option<String>,
+        prices: BTreeMap<String, String>,
+        #[serde(default = "default_source_decimals")]
+        decimals: u32,
+    },
+}
+
+/// Endpoint of an exchange REST API.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExchangeConfig {
+    /// Name of the source in the logs & metrics. Defaults to the exchange name, followed by the
+    /// URL when it isn't the public endpoint.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Base URL of the API, the public endpoint of the exchange when not provided.
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Symbols of the pairs on the exchange (example: `"BTC/USD" = "BTCUSDT"`), when they
+    /// differ from the default ones.
+    #[serde(default)]
+    pub symbols: HashMap<String, String>,
+    /// Decimals of the published prices.
+    #[serde(default = "default_source_decimals")]
+    pub decimals: u32,
+    /// Timeout of the requests, in seconds.
+    #[serde(default = "default_timeout")]
+    pub timeout: u64,
+}
+
+/// Describes how the feeder fetches and publishes prices.
+///
+/// Loaded from a TOML file, example:
+/// ```toml
+/// pairs = ["BTC/USD", "ETH/USD"]
+/// interval = 10
+/// heartbeat = 300
+/// deviation_bps = 50
+/// min_sources = 2
+/// outlier_bps = 500
+///
+/// [[sources]]
+/// type = "binance"
+/// symbols = { "BTC/USD" = "BTCUSDT", "ETH/USD" = "ETHUSDT" }
+///
+/// [[sources]]
+/// type = "file"
+/// path = "prices.csv"
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FeederConfig {
+    /// Pairs to publish (format example: "BTC/USD").
+    pub pairs: Vec<String>,
+    /// Sources of the prices, the published price of a pair is the median of their prices.
+    pub sources: Vec<SourceConfig>,
+    /// Minimum number of sources that must provide a price for it to be published.
+    #[serde(default = "default_min_sources")]
+    pub min_sources: usize,
+    /// Prices further than this from the median of all the sources are ignored, in basis
+    /// points. Disabled while 0.
+    #[serde(default = "default_outlier_bps")]
+    pub outlier_bps: u64,
+    /// Seconds between two fetches of the sources.
+    #[serde(default = "default_interval")]
+    pub interval: u64,
+    /// Seconds after which a price is published again, even if it didn't move.
+    #[serde(default = "default_heartbeat")]
+    pub heartbeat: u64,
+    /// Move of a price since its last publication triggering a new one, in basis points.
+    #[serde(default = "default_deviation_bps")]
+    pub deviation_bps: u64,
+    /// Maximum number of entries published in a single transaction.
+    #[serde(default = "default_max_batch_size")]
+    pub max_batch_size: usize,
+    /// Number of times a failed submission is retried before giving up until the next fetch.
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Seconds before the first retry, doubled at each new attempt.
+    #[serde(default = "default_retry_delay")]
+    pub retry_delay: u64,
+}
+
+impl FeederConfig {
+    /// Loads the config from a TOML file.
+    /// Relative source paths are resolved against the directory of the config file.
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Could not read the feeder config {}", path.display()))?;
+        let mut config = Self::from_toml_str(&content)?;
+
+        if let Some(parent) = path.parent() {
+            for source in config.sources.iter_mut() {
+                if let SourceConfig::File { path } | SourceConfig::Replay { path, .. } = source {
+                    if path.is_relative() {
+                        *path = parent.join(path.as_path());
+                    }
+                }
+            }
+        }
+
+        Ok(config)
+    }
+
+    pub fn from_toml_str(content: &str) -> anyhow::Result<Self> {
+        let config: Self = toml::from_str(content).context("Invalid TOML feeder config")?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.pairs()?.is_empty() {
+            anyhow::bail!("The feeder needs at least one pair");
+        }
+        if self.sources.is_empty() {
+            anyhow::bail!("The feeder needs at least one source");
+        }
+        if self.min_sources == 0 || self.min_sources > self.sources.len() {
+            anyhow::bail!(
+                "The min sources must be between 1 and the number of sources ({})",
+                self.sources.len()
+            );
+        }
+        if self.interval == 0 {
+            anyhow::bail!("The interval must be at least 1 second");
+        }
+        if self.max_batch_size == 0 {
+            anyhow::bail!("The max batch size must be at least 1");
+        }
+        Ok(())
+    }
+
+    pub fn pairs(&self) -> anyhow::Result<Vec<Pair>> {
+        self.pairs
+            .iter()
+            .map(|pair| Pair::from_str(pair).with_context(|| format!("Invalid pair {pair}")))
+            .collect()
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval)
+    }
+
+    pub fn retry_delay(&self) -> Duration {
+        Duration::from_secs(self.retry_delay)
+    }
+}
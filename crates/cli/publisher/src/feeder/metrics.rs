@@ -0,0 +1,59 @@
+use std::collections::BTreeMap;
+
+use colored::*;
+use prettytable::{Cell, Row, Table};
+use serde::Serialize;
+
+/// Counters of a price source since the feeder started.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SourceMetrics {
+    /// Prices kept in the aggregated price of a pair.
+    pub contributed: u64,
+    /// Prices rejected as outliers.
+    pub rejected: u64,
+    /// Failed fetches.
+    pub failed: u64,
+}
+
+/// Counters of the feeder since it started.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FeederMetrics {
+    pub published_entries: u64,
+    pub failed_submissions: u64,
+    pub sources: BTreeMap<String, SourceMetrics>,
+}
+
+impl FeederMetrics {
+    pub fn source(&mut self, name: &str) -> &mut SourceMetrics {
+        self.sources.entry(name.to_string()).or_default()
+    }
+
+    pub fn print(&self) {
+        println!(
+            "{}",
+            format!(
+                "📈 {} entries published, {} failed submissions\n",
+                self.published_entries, self.failed_submissions
+            )
+            .bright_yellow()
+        );
+
+        let mut table = Table::new();
+        table.set_format(*prettytable::format::consts::FORMAT_BOX_CHARS);
+        table.add_row(Row::new(vec![
+            Cell::new("Source").style_spec("Fcb"),
+            Cell::new("Contributed").style_spec("Fcb"),
+            Cell::new("Rejected").style_spec("Fcb"),
+            Cell::new("Failed").style_spec("Fcb"),
+        ]));
+        for (name, metrics) in self.sources.iter() {
+            table.add_row(Row::new(vec![
+                Cell::new(name).style_spec("Fg"),
+                Cell::new(&metrics.contributed.to_string()).style_spec("Fw"),
+                Cell::new(&metrics.rejected.to_string()).style_spec("Fy"),
+                Cell::new(&metrics.failed.to_string()).style_spec("Fr"),
+            ]));
+        }
+        table.printstd();
+    }
+}
@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+type Routes = Arc<Mutex<HashMap<String, String>>>;
+
+/// Minimal in-process HTTP server answering GET requests with fixed JSON bodies, so the
+/// exchange sources can be exercised offline.
+pub struct MockHttpServer {
+    url: String,
+    routes: Routes,
+    handle: JoinHandle<()>,
+}
+
+impl MockHttpServer {
+    /// Starts the server on a random local port.
+    pub async fn start() -> anyhow::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let routes = Routes::default();
+
+        let server_routes = routes.clone();
+        let handle = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(respond(stream, server_routes.clone()));
+            }
+        });
+
+        Ok(Self {
+            url,
+            routes,
+            handle,
+        })
+    }
+
+    /// Base URL of the server, example: `http://127.0.0.1:41233`.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Answers the requests to `path` (including the query string) with `body`.
+    pub fn route(&self, path: &str, body: &str) {
+        self.routes
+            .lock()
+            .unwrap()
+            .insert(path.to_string(), body.to_string());
+    }
+}
+
+impl Drop for MockHttpServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+async fn respond(mut stream: TcpStream, routes: Routes) {
+    let mut request = Vec::new();
+    let mut buffer = [0_u8; 1024];
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        match stream.read(&mut buffer).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => request.extend_from_slice(&buffer[..n]),
+        }
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let path = request.split_whitespace().nth(1).unwrap_or_default();
+    let body = routes.lock().unwrap().get(path).cloned();
+    let response = match body {
+        Some(body) => format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        ),
+        None => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            .to_string(),
+    };
+    let _ = stream.write_all(response.as_bytes()).await;
+}
@@ -0,0 +1,464 @@
+//! Long running price feeder: periodically pulls the prices of the configured pairs from the
+//! sources, and publishes the ones that moved enough or weren't published for too long.
+
+pub mod aggregation;
+pub mod config;
+pub mod metrics;
+#[cfg(any(test, feature = "testing"))]
+pub mod mock_server;
+pub mod policy;
+pub mod sources;
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use colored::*;
+use miden_client::{
+    accounts::AccountId, crypto::FeltRng, transactions::TransactionId, Client, ZERO,
+};
+use serde_json::json;
+
+use pm_accounts::publisher::{publisher_account_slot, ENTRIES_MAP_SLOT};
+use pm_types::{Entry, Pair};
+use pm_utils_cli::{entry_json, get_account, print_json, sync_client, OutputFormat};
+
+use crate::commands::publish::check_timestamp;
+use crate::commands::publish_batch::submit_entries;
+pub use aggregation::{AggregatedEntry, AggregationPolicy};
+pub use config::{ExchangeConfig, FeederConfig, SourceConfig};
+pub use metrics::{FeederMetrics, SourceMetrics};
+pub use policy::PublishPolicy;
+pub use sources::{
+    parse_price, ExchangeFormat, ExchangeSource, FileSource, MockSource, PriceSource, ReplaySource,
+};
+
+/// Cap of the delay between two retries.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+pub struct Feeder {
+    config: FeederConfig,
+    pairs: Vec<Pair>,
+    policy: PublishPolicy,
+    aggregation: AggregationPolicy,
+    sources: Vec<Box<dyn PriceSource>>,
+    publisher_id: AccountId,
+    /// Last entry published for each pair, keyed by the pair name.
+    last_published: HashMap<String, Entry>,
+    backoff: Backoff,
+    metrics: FeederMetrics,
+    output: OutputFormat,
+}
+
+impl Feeder {
+    pub fn new(config: FeederConfig, publisher_id: AccountId) -> anyhow::Result<Self> {
+        let pairs = config.pairs()?;
+        let policy = PublishPolicy {
+            deviation_bps: config.deviation_bps,
+            heartbeat: config.heartbeat,
+        };
+        let aggregation = AggregationPolicy {
+            min_sources: config.min_sources,
+            outlier_bps: config.outlier_bps,
+        };
+        let sources: Vec<Box<dyn PriceSource>> = config
+            .sources
+            .iter()
+            .map(SourceConfig::build)
+            .collect::<anyhow::Result<_>>()?;
+        // The prices & metrics are attributed to the sources by name
+        let mut names = HashSet::new();
+        if let Some(source) = sources.iter().find(|source| !names.insert(source.name())) {
+            anyhow::bail!(
+                "Several sources are named {}, set a distinct `name` on each of them",
+                source.name()
+            );
+        }
+        let backoff = Backoff::new(config.retry_delay(), config.max_retries);
+        Ok(Self {
+            config,
+            pairs,
+            policy,
+            aggregation,
+            sources,
+            publisher_id,
+            last_published: HashMap::new(),
+            backoff,
+            metrics: FeederMetrics::default(),
+            output: OutputFormat::default(),
+        })
+    }
+
+    /// With the JSON output, each publication & the final metrics are printed as a line of
+    /// JSON instead of the progress messages.
+    pub fn with_output(mut self, output: OutputFormat) -> Self {
+        self.output = output;
+        self
+    }
+
+    /// Runs the feeder until Ctrl+C is received. A batch being submitted is always completed
+    /// before exiting.
+    pub async fn run(&mut self, client: &mut Client<impl FeltRng>) -> anyhow::Result<()> {
+        self.load_with_retry(client).await?;
+
+        let mut interval = tokio::time::interval(self.config.interval());
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let shutdown = tokio::signal::ctrl_c();
+        tokio::pin!(shutdown);
+
+        if !self.output.is_json() {
+            println!(
+                "{}",
+                format!(
+                    "🚀 Feeding {} pairs from {} sources every {}s",
+                    self.pairs.len(),
+                    self.sources.len(),
+                    self.config.interval
+                )
+                .bright_green()
+            );
+        }
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => {
+                    if self.output.is_json() {
+                        return print_json(&json!({ "event": "shutdown", "metrics": self.metrics }));
+                    }
+                    println!("{}", "👋 Shutting down the feeder".bright_yellow());
+                    self.metrics.print();
+                    return Ok(());
+                }
+                _ = interval.tick() => {
+                    if let Err(e) = self.tick(client).await {
+                        eprintln!("{}", format!("❌ {e:#}").bright_red());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Fetches the sources once and publishes the entries selected by the policy.
+    async fn tick(&mut self, client: &mut Client<impl FeltRng>) -> anyhow::Result<()> {
+        let fetched = self.fetch_entries().await;
+        let entries = self.entries_to_publish(fetched);
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        for batch in entries.chunks(self.config.max_batch_size) {
+            match self.submit_with_retry(client, batch).await {
+                Ok(tx_id) => {
+                    self.metrics.published_entries += batch.len() as u64;
+                    for entry in batch {
+                        if self.output.is_json() {
+                            let mut published = entry_json(self.publisher_id, entry);
+                            published["event"] = "published".into();
+                            published["tx_id"] = tx_id.to_string().into();
+                            print_json(&published)?;
+                        } else {
+                            println!(
+                                "✅ {} published at {} ({} decimals)",
+                                entry.pair, entry.price, entry.decimals
+                            );
+                        }
+                        self.last_published
+                            .insert(entry.pair.to_string(), entry.clone());
+                    }
+                }
+                Err(e) => {
+                    self.metrics.failed_submissions += 1;
+                    eprintln!(
+                        "{}",
+                        format!("❌ Could not publish {} entries: {e:#}", batch.len()).bright_red()
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Entries selected by the policy, skipping the ones too far in the future to be accepted.
+    fn entries_to_publish(&self, entries: Vec<Entry>) -> Vec<Entry> {
+        entries
+            .into_iter()
+            .filter(|entry| match check_timestamp(entry.timestamp) {
+                Ok(()) => true,
+                Err(e) => {
+                    eprintln!("⚠️  Skipping {}: {e}", entry.pair);
+                    false
+                }
+            })
+            .filter(|entry| {
+                self.policy
+                    .should_publish(self.last_published.get(&entry.pair.to_string()), entry)
+            })
+            .collect()
+    }
+
+    /// Price of each pair, aggregated from the prices of all the sources providing it.
+    async fn fetch_entries(&mut self) -> Vec<Entry> {
+        let mut fetched_by_pair: HashMap<String, Vec<(String, Entry)>> = HashMap::new();
+        for source in self.sources.iter() {
+            match source.fetch(&self.pairs).await {
+                Ok(fetched) => {
+                    for entry in fetched {
+                        fetched_by_pair
+                            .entry(entry.pair.to_string())
+                            .or_default()
+                            .push((source.name().to_string(), entry));
+                    }
+                }
+                Err(e) => {
+                    self.metrics.source(source.name()).failed += 1;
+                    eprintln!("⚠️  Source {} failed: {e:#}", source.name());
+                }
+            }
+        }
+
+        let mut entries = Vec::with_capacity(fetched_by_pair.len());
+        for (pair, fetched) in fetched_by_pair {
+            match self.aggregation.aggregate(fetched) {
+                Ok(aggregated) => {
+                    for source in aggregated.sources.iter() {
+                        self.metrics.source(source).contributed += 1;
+                    }
+                    for source in aggregated.rejected.iter() {
+                        self.metrics.source(source).rejected += 1;
+                    }
+                    let rejected = if aggregated.rejected.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" (rejected: {})", aggregated.rejected.join(", "))
+                    };
+                    if !self.output.is_json() {
+                        println!(
+                            "📊 {}: {} from {}{}",
+                            pair,
+                            aggregated.entry.price,
+                            aggregated.sources.join(", "),
+                            rejected
+                        );
+                    }
+                    entries.push(aggregated.entry);
+                }
+                Err(e) => eprintln!("⚠️  Skipping {pair}: {e:#}"),
+            }
+        }
+        entries
+    }
+
+    async fn submit_with_retry(
+        &mut self,
+        client: &mut Client<impl FeltRng>,
+        entries: &[Entry],
+    ) -> anyhow::Result<TransactionId> {
+        loop {
+            let result = match sync_client(client).await {
+                Ok(_) => submit_entries(client, self.publisher_id, entries).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(tx_id) => {
+                    self.backoff.reset();
+                    return Ok(tx_id);
+                }
+                Err(e) => self.backoff.retry("Submission", e).await?,
+            }
+        }
+    }
+
+    /// Syncs & loads the published entries at startup, retried like the submissions so a node
+    /// briefly unreachable doesn't stop the feeder.
+    async fn load_with_retry(&mut self, client: &mut Client<impl FeltRng>) -> anyhow::Result<()> {
+        loop {
+            let result = match sync_client(client).await {
+                Ok(_) => self.load_published_entries(client).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(()) => {
+                    self.backoff.reset();
+                    return Ok(());
+                }
+                Err(e) => {
+                    self.backoff
+                        .retry("Loading the published entries", e)
+                        .await?
+                }
+            }
+        }
+    }
+
+    /// Loads the entries already published by the account, so a restart doesn't publish
+    /// prices that didn't move.
+    async fn load_published_entries(
+        &mut self,
+        client: &mut Client<impl FeltRng>,
+    ) -> anyhow::Result<()> {
+        let publisher = get_account(client, self.publisher_id).await?;
+
+        for pair in self.pairs.iter() {
+            let entry = publisher
+                .storage()
+                .get_map_item(publisher_account_slot(ENTRIES_MAP_SLOT), pair.to_word())?;
+            if entry != [ZERO; 4] {
+                self.last_published
+                    .insert(pair.to_string(), Entry::from(entry));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Delay between the attempts of a node operation, starting at the configured retry delay and
+/// doubled at each attempt, up to a minute. It starts over once the operation succeeded or gave
+/// up.
+struct Backoff {
+    initial_delay: Duration,
+    delay: Duration,
+    attempt: u32,
+    max_retries: u32,
+}
+
+impl Backoff {
+    fn new(initial_delay: Duration, max_retries: u32) -> Self {
+        Self {
+            initial_delay,
+            delay: initial_delay,
+            attempt: 0,
+            max_retries,
+        }
+    }
+
+    /// Delay before the next attempt, `None` once the retries are exhausted.
+    fn next_delay(&mut self) -> Option<Duration> {
+        if self.attempt >= self.max_retries {
+            return None;
+        }
+        self.attempt += 1;
+        let delay = self.delay;
+        self.delay = self.delay.saturating_mul(2).min(MAX_RETRY_DELAY);
+        Some(delay)
+    }
+
+    fn reset(&mut self) {
+        self.delay = self.initial_delay;
+        self.attempt = 0;
+    }
+
+    /// Waits before the next attempt, or gives the error back once the retries are exhausted.
+    async fn retry(&mut self, operation: &str, e: anyhow::Error) -> anyhow::Result<()> {
+        let Some(delay) = self.next_delay() else {
+            self.reset();
+            return Err(e);
+        };
+        eprintln!(
+            "⚠️  {operation} failed, retrying in {}s ({}/{}): {e:#}",
+            delay.as_secs(),
+            self.attempt,
+            self.max_retries
+        );
+        tokio::time::sleep(delay).await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn entry(pair: &str, price: u64, timestamp: u64) -> Entry {
+        Entry {
+            pair: Pair::from_str(pair).unwrap(),
+            price,
+            decimals: 2,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_feeder_entries_to_publish() {
+        let config = FeederConfig::from_toml_str(
+            r#"
+            pairs = ["BTC/USD", "ETH/USD", "SOL/USD"]
+            heartbeat = 300
+            deviation_bps = 50
+
+            [[sources]]
+            type = "mock"
+            prices = { "BTC/USD" = "97588.12" }
+            "#,
+        )
+        .unwrap();
+        let publisher_id = AccountId::from_hex("0x800000000000001f").unwrap();
+        let mut feeder = Feeder::new(config, publisher_id).unwrap();
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        for last in [
+            entry("BTC/USD", 100_000, now - 10),
+            entry("ETH/USD", 100_000, now - 10),
+        ] {
+            feeder.last_published.insert(last.pair.to_string(), last);
+        }
+
+        let selected = feeder.entries_to_publish(vec![
+            // Moved by 1%
+            entry("BTC/USD", 101_000, now),
+            // Didn't move enough before the heartbeat
+            entry("ETH/USD", 100_100, now),
+            // Never published, but too far in the future
+            entry("SOL/USD", 20_000, now + 3_600),
+        ]);
+        let selected: Vec<String> = selected
+            .iter()
+            .map(|entry| entry.pair.to_string())
+            .collect();
+        assert_eq!(selected, ["BTC/USD"]);
+    }
+
+    #[test]
+    fn test_feeder_source_names_are_unique() {
+        let publisher_id = AccountId::from_hex("0x800000000000001f").unwrap();
+        let config = |second_name: &str| {
+            FeederConfig::from_toml_str(&format!(
+                r#"
+                pairs = ["BTC/USD"]
+
+                [[sources]]
+                type = "mock"
+                prices = {{ "BTC/USD" = "97588.12" }}
+
+                [[sources]]
+                type = "mock"
+                {second_name}
+                prices = {{ "BTC/USD" = "97590.00" }}
+                "#
+            ))
+            .unwrap()
+        };
+
+        assert!(Feeder::new(config(""), publisher_id).is_err());
+        let feeder = Feeder::new(config(r#"name = "mock-2""#), publisher_id).unwrap();
+        let names: Vec<&str> = feeder.sources.iter().map(|source| source.name()).collect();
+        assert_eq!(names, ["mock", "mock-2"]);
+    }
+
+    #[test]
+    fn test_backoff_grows_then_resets() {
+        let mut backoff = Backoff::new(Duration::from_secs(20), 4);
+
+        let delays: Vec<Option<u64>> = (0..5)
+            .map(|_| backoff.next_delay().map(|delay| delay.as_secs()))
+            .collect();
+        assert_eq!(delays, [Some(20), Some(40), Some(60), Some(60), None]);
+
+        // A success starts over from the initial delay
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), Some(Duration::from_secs(20)));
+    }
+}
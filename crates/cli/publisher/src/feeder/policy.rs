@@ -0,0 +1,29 @@
+use pm_types::Entry;
+
+use super::aggregation::deviation_bps;
+
+/// Decides whether a fetched price must be published, given the last published entry of its
+/// pair.
+#[derive(Debug, Clone, Copy)]
+pub struct PublishPolicy {
+    /// Move of the price triggering a publication, in basis points.
+    pub deviation_bps: u64,
+    /// Seconds after which the price is published again, even if it didn't move.
+    pub heartbeat: u64,
+}
+
+impl PublishPolicy {
+    pub fn should_publish(&self, last: Option<&Entry>, entry: &Entry) -> bool {
+        let Some(last) = last else {
+            return true;
+        };
+        // The publisher account rejects entries that aren't strictly more recent
+        if entry.timestamp <= last.timestamp {
+            return false;
+        }
+        if entry.timestamp - last.timestamp >= self.heartbeat || entry.decimals != last.decimals {
+            return true;
+        }
+        deviation_bps(last.price, entry.price) > self.deviation_bps as u128
+    }
+}
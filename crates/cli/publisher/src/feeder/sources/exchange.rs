@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use serde_json::Value;
+
+use pm_types::{Entry, Pair};
+
+use super::{now, parse_price, PriceSource};
+use crate::feeder::config::ExchangeConfig;
+
+/// REST ticker formats of the supported exchanges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExchangeFormat {
+    /// `GET /api/v3/ticker/price?symbol=BTCUSDT` returning `{"symbol": "BTCUSDT", "price": "97588.12"}`
+    Binance,
+    /// `GET /products/BTC-USD/ticker` returning `{"price": "97588.12", ...}`
+    Coinbase,
+    /// `GET /0/public/Ticker?pair=BTCUSD` returning
+    /// `{"error": [], "result": {"XXBTZUSD": {"c": ["97588.12", "0.01"], ...}}}`
+    Kraken,
+}
+
+impl ExchangeFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExchangeFormat::Binance => "binance",
+            ExchangeFormat::Coinbase => "coinbase",
+            ExchangeFormat::Kraken => "kraken",
+        }
+    }
+
+    pub fn default_url(&self) -> &'static str {
+        match self {
+            ExchangeFormat::Binance => "https://api.binance.com",
+            ExchangeFormat::Coinbase => "https://api.exchange.coinbase.com",
+            ExchangeFormat::Kraken => "https://api.kraken.com",
+        }
+    }
+
+    /// Symbol of the pair on the exchange, when not overridden in the config.
+    /// Binance doesn't quote in USD, its USD pairs default to the USDT ones.
+    pub fn default_symbol(&self, pair: &Pair) -> String {
+        match self {
+            ExchangeFormat::Binance if pair.quote.0 == "USD" => format!("{}USDT", pair.base.0),
+            ExchangeFormat::Coinbase => format!("{}-{}", pair.base.0, pair.quote.0),
+            ExchangeFormat::Binance | ExchangeFormat::Kraken => {
+                format!("{}{}", pair.base.0, pair.quote.0)
+            }
+        }
+    }
+
+    pub fn ticker_path(&self, symbol: &str) -> String {
+        match self {
+            ExchangeFormat::Binance => format!("/api/v3/ticker/price?symbol={symbol}"),
+            ExchangeFormat::Coinbase => format!("/products/{symbol}/ticker"),
+            ExchangeFormat::Kraken => format!("/0/public/Ticker?pair={symbol}"),
+        }
+    }
+
+    /// Extracts the last price from a ticker response.
+    pub fn parse_ticker(&self, ticker: &Value) -> anyhow::Result<String> {
+        let price = match self {
+            ExchangeFormat::Binance | ExchangeFormat::Coinbase => ticker.get("price"),
+            ExchangeFormat::Kraken => {
+                if let Some(errors) = ticker.get("error").and_then(Value::as_array) {
+                    if !errors.is_empty() {
+                        anyhow::bail!("Kraken returned errors: {errors:?}");
+                    }
+                }
+                ticker
+                    .get("result")
+                    .and_then(Value::as_object)
+                    .and_then(|result| result.values().next())
+                    .and_then(|pair_ticker| pair_ticker.get("c"))
+                    .and_then(|last_trade| last_trade.get(0))
+            }
+        };
+        price
+            .and_then(Value::as_str)
+            .map(String::from)
+            .ok_or_else(|| anyhow::anyhow!("No price found in the {} ticker", self.name()))
+    }
+}
+
+/// Fetches the prices from the REST ticker endpoint of an exchange.
+pub struct ExchangeSource {
+    name: String,
+    format: ExchangeFormat,
+    url: String,
+    symbols: HashMap<String, String>,
+    decimals: u32,
+    client: reqwest::Client,
+}
+
+impl ExchangeSource {
+    pub fn new(format: ExchangeFormat, config: &ExchangeConfig) -> anyhow::Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout))
+            .build()
+            .context("Could not build the HTTP client")?;
+        let url = config
+            .url
+            .clone()
+            .unwrap_or_else(|| format.default_url().to_string())
+            .trim_end_matches('/')
+            .to_string();
+        let name = match (&config.name, &config.url) {
+            (Some(name), _) => name.clone(),
+            (None, Some(_)) => format!("{}:{url}", format.name()),
+            (None, None) => format.name().to_string(),
+        };
+        Ok(Self {
+            name,
+            format,
+            url,
+            symbols: config.symbols.clone(),
+            decimals: config.decimals,
+            client,
+        })
+    }
+
+    async fn fetch_pair(&self, pair: &Pair) -> anyhow::Result<Entry> {
+        let symbol = self
+            .symbols
+            .get(&pair.to_string())
+            .cloned()
+            .unwrap_or_else(|| self.format.default_symbol(pair));
+        let url = format!("{}{}", self.url, self.format.ticker_path(&symbol));
+
+        let ticker: Value = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .with_context(|| format!("Request to {url} failed"))?
+            .json()
+            .await
+            .with_context(|| format!("Invalid JSON returned by {url}"))?;
+        let price = self.format.parse_ticker(&ticker)?;
+
+        Ok(Entry {
+            pair: pair.clone(),
+            price: parse_price(&price, self.decimals)?,
+            decimals: self.decimals,
+            timestamp: now()?,
+        })
+    }
+}
+
+#[async_trait]
+impl PriceSource for ExchangeSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn fetch(&self, pairs: &[Pair]) -> anyhow::Result<Vec<Entry>> {
+        let mut entries = Vec::with_capacity(pairs.len());
+        let mut last_error = None;
+        for pair in pairs {
+            match self.fetch_pair(pair).await {
+                Ok(entry) => entries.push(entry),
+                Err(e) => {
+                    eprintln!("⚠️  {}: no price for {pair}: {e:#}", self.name());
+                    last_error = Some(e);
+                }
+            }
+        }
+        match last_error {
+            Some(e) if entries.is_empty() => Err(e),
+            _ => Ok(entries),
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use pm_types::{Entry, Pair};
+
+use super::{now, same_pair, PriceSource};
+use crate::commands::publish_batch::read_entries;
+
+/// Reads the entries of a CSV or JSON file, in the `publish-batch` format. The file is read
+/// again at every fetch, so another process can keep it updated.
+pub struct FileSource {
+    name: String,
+    path: PathBuf,
+}
+
+impl FileSource {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            name: format!("file:{}", path.display()),
+            path,
+        }
+    }
+}
+
+#[async_trait]
+impl PriceSource for FileSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn fetch(&self, pairs: &[Pair]) -> anyhow::Result<Vec<Entry>> {
+        let entries = read_entries(&self.path)?;
+        Ok(entries
+            .into_iter()
+            .filter(|entry| pairs.iter().any(|pair| same_pair(pair, &entry.pair)))
+            .collect())
+    }
+}
+
+/// Replays the prices of a recorded file, in the `publish-batch` format: every fetch returns
+/// the next recorded price of each pair, timestamped with the current time.
+pub struct ReplaySource {
+    name: String,
+    repeat: bool,
+    /// Recorded entries of each pair by timestamp, and the index of the next one.
+    entries: Mutex<HashMap<String, (Vec<Entry>, usize)>>,
+}
+
+impl ReplaySource {
+    pub fn from_file(path: &Path, repeat: bool) -> anyhow::Result<Self> {
+        Ok(Self::new(
+            format!("replay:{}", path.display()),
+            read_entries(path)?,
+            repeat,
+        ))
+    }
+
+    /// Replays the entries, once or in a loop when `repeat` is set.
+    pub fn new(name: String, entries: Vec<Entry>, repeat: bool) -> Self {
+        let mut by_pair: HashMap<String, (Vec<Entry>, usize)> = HashMap::new();
+        for entry in entries {
+            by_pair
+                .entry(entry.pair.to_string())
+                .or_default()
+                .0
+                .push(entry);
+        }
+        for (entries, _) in by_pair.values_mut() {
+            entries.sort_by_key(|entry| entry.timestamp);
+        }
+        Self {
+            name,
+            repeat,
+            entries: Mutex::new(by_pair),
+        }
+    }
+}
+
+#[async_trait]
+impl PriceSource for ReplaySource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn fetch(&self, pairs: &[Pair]) -> anyhow::Result<Vec<Entry>> {
+        let timestamp = now()?;
+        let mut entries = self.entries.lock().unwrap();
+        let mut replayed = Vec::new();
+        for pair in pairs {
+            let Some((recorded, next)) = entries.get_mut(&pair.to_string()) else {
+                continue;
+            };
+            if *next == recorded.len() && self.repeat {
+                *next = 0;
+            }
+            if let Some(entry) = recorded.get(*next) {
+                replayed.push(Entry {
+                    timestamp,
+                    ..entry.clone()
+                });
+                *next += 1;
+            }
+        }
+        Ok(replayed)
+    }
+}
@@ -0,0 +1,60 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use anyhow::Context;
+use async_trait::async_trait;
+
+use pm_types::{Entry, Pair};
+
+use super::{now, parse_price, same_pair, PriceSource};
+
+/// Returns fixed prices, timestamped with the current time. Useful to run a feeder offline.
+pub struct MockSource {
+    name: String,
+    decimals: u32,
+    prices: Vec<(Pair, u64)>,
+}
+
+impl MockSource {
+    /// `prices` maps the pairs (format example: "BTC/USD") to decimal prices.
+    pub fn new(
+        name: String,
+        prices: BTreeMap<String, String>,
+        decimals: u32,
+    ) -> anyhow::Result<Self> {
+        let prices = prices
+            .iter()
+            .map(|(pair, price)| {
+                let pair = Pair::from_str(pair).with_context(|| format!("Invalid pair {pair}"))?;
+                Ok((pair, parse_price(price, decimals)?))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self {
+            name,
+            decimals,
+            prices,
+        })
+    }
+}
+
+#[async_trait]
+impl PriceSource for MockSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn fetch(&self, pairs: &[Pair]) -> anyhow::Result<Vec<Entry>> {
+        let timestamp = now()?;
+        Ok(self
+            .prices
+            .iter()
+            .filter(|(pair, _)| pairs.iter().any(|requested| same_pair(requested, pair)))
+            .map(|(pair, price)| Entry {
+                pair: pair.clone(),
+                price: *price,
+                decimals: self.decimals,
+                timestamp,
+            })
+            .collect())
+    }
+}
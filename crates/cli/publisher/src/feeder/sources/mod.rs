@@ -0,0 +1,87 @@
+mod exchange;
+mod file;
+mod mock;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+use async_trait::async_trait;
+
+use pm_types::{Entry, Pair};
+
+use super::config::SourceConfig;
+pub use exchange::{ExchangeFormat, ExchangeSource};
+pub use file::{FileSource, ReplaySource};
+pub use mock::MockSource;
+
+/// Provides the latest prices of a set of pairs.
+#[async_trait]
+pub trait PriceSource: Send + Sync {
+    /// Name of the source, used in the logs & metrics. Unique among the sources of a feeder.
+    fn name(&self) -> &str;
+
+    /// Fetches the latest entries of the pairs. Pairs unknown to the source are skipped.
+    async fn fetch(&self, pairs: &[Pair]) -> anyhow::Result<Vec<Entry>>;
+}
+
+impl SourceConfig {
+    pub fn build(&self) -> anyhow::Result<Box<dyn PriceSource>> {
+        Ok(match self {
+            SourceConfig::File { path } => Box::new(FileSource::new(path.clone())),
+            SourceConfig::Replay { path, repeat } => {
+                Box::new(ReplaySource::from_file(path, *repeat)?)
+            }
+            SourceConfig::Binance(config) => {
+                Box::new(ExchangeSource::new(ExchangeFormat::Binance, config)?)
+            }
+            SourceConfig::Coinbase(config) => {
+                Box::new(ExchangeSource::new(ExchangeFormat::Coinbase, config)?)
+            }
+            SourceConfig::Kraken(config) => {
+                Box::new(ExchangeSource::new(ExchangeFormat::Kraken, config)?)
+            }
+            SourceConfig::Mock {
+                name,
+                prices,
+                decimals,
+            } => Box::new(MockSource::new(
+                name.clone().unwrap_or_else(|| "mock".to_string()),
+                prices.clone(),
+                *decimals,
+            )?),
+        })
+    }
+}
+
+/// Current UNIX timestamp in seconds, used for the sources that don't provide one.
+pub(crate) fn now() -> anyhow::Result<u64> {
+    Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs())
+}
+
+/// Converts a decimal price (format example: "97588.12") to an integer with `decimals`
+/// decimals, the extra digits are truncated.
+pub fn parse_price(price: &str, decimals: u32) -> anyhow::Result<u64> {
+    let price = price.trim();
+    let (integer, fraction) = price.split_once('.').unwrap_or((price, ""));
+    if (integer.is_empty() && fraction.is_empty())
+        || !integer
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        anyhow::bail!("Invalid price {price}");
+    }
+
+    let fraction: String = fraction
+        .chars()
+        .chain(std::iter::repeat('0'))
+        .take(decimals as usize)
+        .collect();
+    format!("{integer}{fraction}")
+        .parse::<u64>()
+        .with_context(|| format!("Price {price} doesn't fit in {decimals} decimals"))
+}
+
+fn same_pair(a: &Pair, b: &Pair) -> bool {
+    a.to_word() == b.to_word()
+}
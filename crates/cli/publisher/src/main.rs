@@ -1,6 +1,6 @@
 use clap::Parser;
-pub mod commands;
-use commands::SubCommand;
+use pm_publisher_cli::commands::SubCommand;
+use pm_utils_cli::{exit_with_error, ClientArgs, ClientConfig};
 
 #[derive(Parser, Debug)]
 #[command(name = "pm-publisher")]
@@ -8,11 +8,19 @@ use commands::SubCommand;
 struct Cli {
     #[command(subcommand)]
     command: SubCommand,
+    #[command(flatten)]
+    client: ClientArgs,
 }
 
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
+async fn main() {
     let cli = Cli::parse();
-    cli.command.call().await?;
-    Ok(())
+    if let Err(e) = run(&cli).await {
+        exit_with_error(cli.client.output, &e);
+    }
+}
+
+async fn run(cli: &Cli) -> anyhow::Result<()> {
+    let client_config = ClientConfig::load(&cli.client)?;
+    cli.command.call(&client_config).await
 }
@@ -0,0 +1,314 @@
+use std::str::FromStr;
+
+use pm_publisher_cli::feeder::{
+    mock_server::MockHttpServer, parse_price, AggregationPolicy, FeederConfig, PriceSource,
+    PublishPolicy, ReplaySource,
+};
+use pm_types::{Entry, Pair};
+
+#[tokio::test]
+async fn test_feeder_exchange_sources() {
+    let server = MockHttpServer::start().await.unwrap();
+    server.route(
+        "/api/v3/ticker/price?symbol=BTCUSDT",
+        r#"{"symbol": "BTCUSDT", "price": "97588.12000000"}"#,
+    );
+    server.route(
+        "/products/BTC-USD/ticker",
+        r#"{"ask": "97590.01", "bid": "97587.00", "price": "97588.5", "time": "2024-12-10T15:20:00Z"}"#,
+    );
+    server.route(
+        "/0/public/Ticker?pair=BTCUSD",
+        r#"{"error": [], "result": {"XXBTZUSD": {"a": ["97589.0", "1", "1.000"], "c": ["97588.10000", "0.00100000"]}}}"#,
+    );
+
+    let config = FeederConfig::from_toml_str(&format!(
+        r#"
+        pairs = ["BTC/USD", "ETH/USD"]
+
+        [[sources]]
+        type = "binance"
+        name = "binance"
+        url = "{url}"
+
+        [[sources]]
+        type = "coinbase"
+        url = "{url}"
+        decimals = 6
+
+        [[sources]]
+        type = "kraken"
+        url = "{url}/"
+        "#,
+        url = server.url()
+    ))
+    .unwrap();
+    let pairs = config.pairs().unwrap();
+
+    // Named after the exchange & the URL when the name isn't set
+    let expected = [
+        ("binance".to_string(), 9_758_812_000_000, 8),
+        (format!("coinbase:{}", server.url()), 97_588_500_000, 6),
+        (format!("kraken:{}", server.url()), 9_758_810_000_000, 8),
+    ];
+    for (source_config, (name, price, decimals)) in config.sources.iter().zip(expected) {
+        let source = source_config.build().unwrap();
+        assert_eq!(source.name(), name);
+
+        // ETH/USD isn't served, only the BTC/USD price is returned
+        let entries = source.fetch(&pairs).await.unwrap();
+        assert_eq!(entries.len(), 1, "{name}");
+        assert_eq!(entries[0].pair.to_string(), "BTC/USD");
+        assert_eq!(entries[0].price, price, "{name}");
+        assert_eq!(entries[0].decimals, decimals, "{name}");
+    }
+
+    // A source without any price fails
+    let eth_only = [Pair::from_str("ETH/USD").unwrap()];
+    assert!(config.sources[0]
+        .build()
+        .unwrap()
+        .fetch(&eth_only)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_feeder_kraken_errors() {
+    let server = MockHttpServer::start().await.unwrap();
+    server.route(
+        "/0/public/Ticker?pair=BTCUSD",
+        r#"{"error": ["EQuery:Unknown asset pair"]}"#,
+    );
+
+    let config = FeederConfig::from_toml_str(&format!(
+        r#"
+        pairs = ["BTC/USD"]
+
+        [[sources]]
+        type = "kraken"
+        url = "{}"
+        "#,
+        server.url()
+    ))
+    .unwrap();
+    let source = config.sources[0].build().unwrap();
+    assert!(source.fetch(&config.pairs().unwrap()).await.is_err());
+}
+
+#[tokio::test]
+async fn test_feeder_replay_source() {
+    let btc = Pair::from_str("BTC/USD").unwrap();
+    let recorded: Vec<Entry> = [(3, 300), (1, 100), (2, 200)]
+        .into_iter()
+        .map(|(timestamp, price)| Entry {
+            pair: btc.clone(),
+            price,
+            decimals: 8,
+            timestamp,
+        })
+        .collect();
+
+    // Prices are replayed by recorded timestamp, timestamped with the current time
+    let replay = ReplaySource::new("replay".to_string(), recorded.clone(), false);
+    let pairs = [btc.clone()];
+    for expected_price in [100, 200, 300] {
+        let entries = replay.fetch(&pairs).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].price, expected_price);
+        assert!(entries[0].timestamp > 1_733_844_000);
+    }
+    assert!(replay.fetch(&pairs).await.unwrap().is_empty());
+
+    let repeated = ReplaySource::new("replay".to_string(), recorded, true);
+    let prices: Vec<u64> = fetch_prices(&repeated, &pairs, 4).await;
+    assert_eq!(prices, vec![100, 200, 300, 100]);
+}
+
+#[tokio::test]
+async fn test_feeder_mock_source() {
+    let config = FeederConfig::from_toml_str(
+        r#"
+        pairs = ["BTC/USD"]
+
+        [[sources]]
+        type = "mock"
+        decimals = 2
+        prices = { "BTC/USD" = "97588.129", "ETH/USD" = "3900" }
+        "#,
+    )
+    .unwrap();
+    let source = config.sources[0].build().unwrap();
+    let entries = source.fetch(&config.pairs().unwrap()).await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].price, 9_758_812);
+    assert_eq!(entries[0].decimals, 2);
+}
+
+#[test]
+fn test_feeder_parse_price() {
+    assert_eq!(parse_price("97588.12", 8).unwrap(), 9_758_812_000_000);
+    assert_eq!(parse_price("97588", 2).unwrap(), 9_758_800);
+    assert_eq!(parse_price("0.000123456", 6).unwrap(), 123);
+    assert_eq!(parse_price(".5", 1).unwrap(), 5);
+    assert!(parse_price("", 8).is_err());
+    assert!(parse_price("-1.0", 8).is_err());
+    assert!(parse_price("1e5", 8).is_err());
+    assert!(parse_price("184467440737.09551616", 8).is_err());
+}
+
+#[test]
+fn test_feeder_aggregation() {
+    let policy = AggregationPolicy {
+        min_sources: 2,
+        outlier_bps: 100,
+    };
+    let entries = vec![
+        source_entry("binance", 9_758_812, 2, 10),
+        source_entry("coinbase", 97_590_000, 3, 12),
+        source_entry("kraken", 9_758_900, 2, 11),
+        // 10% away from the other sources
+        source_entry("faulty", 10_734_700, 2, 13),
+    ];
+
+    // The prices are converted to 3 decimals, the median ignores the outlier
+    let aggregated = policy.aggregate(entries.clone()).unwrap();
+    assert_eq!(aggregated.entry.price, 97_589_000);
+    assert_eq!(aggregated.entry.decimals, 3);
+    assert_eq!(aggregated.entry.timestamp, 12);
+    assert_eq!(aggregated.sources, vec!["binance", "coinbase", "kraken"]);
+    assert_eq!(aggregated.rejected, vec!["faulty"]);
+
+    // Without outlier rejection, every source is kept
+    let aggregated = AggregationPolicy {
+        min_sources: 1,
+        outlier_bps: 0,
+    }
+    .aggregate(entries.clone())
+    .unwrap();
+    assert_eq!(aggregated.sources.len(), 4);
+    assert_eq!(aggregated.entry.price, 97_589_500);
+
+    // Not enough sources agree
+    let policy = AggregationPolicy {
+        min_sources: 3,
+        outlier_bps: 100,
+    };
+    assert!(policy
+        .aggregate(vec![entries[0].clone(), entries[3].clone()])
+        .is_err());
+    assert!(policy.aggregate(vec![]).is_err());
+}
+
+#[test]
+fn test_feeder_policy_heartbeat() {
+    let policy = PublishPolicy {
+        deviation_bps: 50,
+        heartbeat: 300,
+    };
+    let last = btc_entry(100_000, 1_000);
+
+    assert!(policy.should_publish(None, &last));
+    assert!(!policy.should_publish(Some(&last), &btc_entry(100_000, 1_299)));
+    assert!(policy.should_publish(Some(&last), &btc_entry(100_000, 1_300)));
+}
+
+#[test]
+fn test_feeder_policy_deviation() {
+    let policy = PublishPolicy {
+        deviation_bps: 50,
+        heartbeat: 300,
+    };
+    let last = btc_entry(100_000, 1_000);
+
+    // The price must move by strictly more than the threshold, in both directions
+    assert!(!policy.should_publish(Some(&last), &btc_entry(100_500, 1_010)));
+    assert!(policy.should_publish(Some(&last), &btc_entry(100_510, 1_010)));
+    assert!(policy.should_publish(Some(&last), &btc_entry(99_490, 1_010)));
+
+    // Any price moved from a 0 price
+    let zero = btc_entry(0, 1_000);
+    assert!(policy.should_publish(Some(&zero), &btc_entry(1, 1_010)));
+
+    // A change of decimals is always published
+    let entry = Entry {
+        decimals: 6,
+        ..btc_entry(100_000, 1_010)
+    };
+    assert!(policy.should_publish(Some(&last), &entry));
+}
+
+#[test]
+fn test_feeder_policy_timestamp() {
+    let policy = PublishPolicy {
+        deviation_bps: 50,
+        heartbeat: 300,
+    };
+    let last = btc_entry(100_000, 1_000);
+
+    // The publisher account rejects entries that aren't strictly more recent, even once the
+    // price moved a lot
+    assert!(!policy.should_publish(Some(&last), &btc_entry(200_000, 1_000)));
+    assert!(!policy.should_publish(Some(&last), &btc_entry(200_000, 500)));
+}
+
+#[test]
+fn test_feeder_config_min_sources() {
+    let config = |min_sources: usize| {
+        FeederConfig::from_toml_str(&format!(
+            r#"
+            pairs = ["BTC/USD"]
+            min_sources = {min_sources}
+
+            [[sources]]
+            type = "mock"
+            prices = {{ "BTC/USD" = "97588.12" }}
+
+            [[sources]]
+            type = "binance"
+            "#
+        ))
+    };
+    assert!(config(0).is_err());
+    assert!(config(2).is_ok());
+    assert!(config(3).is_err());
+}
+
+// HELPERS
+// ================================================================================================
+
+fn btc_entry(price: u64, timestamp: u64) -> Entry {
+    Entry {
+        pair: Pair::from_str("BTC/USD").unwrap(),
+        price,
+        decimals: 8,
+        timestamp,
+    }
+}
+
+async fn fetch_prices(source: &dyn PriceSource, pairs: &[Pair], n: usize) -> Vec<u64> {
+    let mut prices = Vec::with_capacity(n);
+    for _ in 0..n {
+        prices.extend(
+            source
+                .fetch(pairs)
+                .await
+                .unwrap()
+                .iter()
+                .map(|entry| entry.price),
+        );
+    }
+    prices
+}
+
+fn source_entry(source: &str, price: u64, decimals: u32, timestamp: u64) -> (String, Entry) {
+    (
+        source.to_string(),
+        Entry {
+            pair: Pair::from_str("BTC/USD").unwrap(),
+            price,
+            decimals,
+            timestamp,
+        },
+    )
+}
@@ -0,0 +1,51 @@
+//! Value parsers validating the arguments of the CLIs before running a command.
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use miden_client::accounts::AccountId;
+use miden_crypto::dsa::rpo_falcon512::PublicKey;
+use pm_accounts::utils::public_key_from_hex;
+use pm_types::Pair;
+
+/// Parses an account id (format example: "0x8c2ad3b9a1e64e07").
+pub fn parse_account_id(id: &str) -> Result<AccountId, String> {
+    AccountId::from_hex(id).map_err(|e| format!("invalid account id, expected 0x<16 hex>: {e}"))
+}
+
+/// Parses a pair (format example: "BTC/USD").
+pub fn parse_pair(pair: &str) -> Result<Pair, String> {
+    Pair::from_str(pair).map_err(|e| format!("invalid pair (format example: BTC/USD): {e}"))
+}
+
+/// Parses a Falcon public key from its hex word representation.
+pub fn parse_public_key(public_key: &str) -> Result<PublicKey, String> {
+    public_key_from_hex(public_key).map_err(|e| e.to_string())
+}
+
+/// Parses a non-zero interval in milliseconds, seconds, minutes or hours (format examples:
+/// "500ms", "5s", "2m", "1h"). A bare number is a number of seconds.
+pub fn parse_interval(interval: &str) -> Result<Duration, String> {
+    let split = interval
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(interval.len());
+    let (value, unit) = interval.split_at(split);
+    let value: u64 = value
+        .parse()
+        .map_err(|_| "invalid interval, expected a number followed by ms, s, m or h".to_string())?;
+    let interval = match unit {
+        "ms" => Duration::from_millis(value),
+        "" | "s" => Duration::from_secs(value),
+        "m" => Duration::from_secs(value.saturating_mul(60)),
+        "h" => Duration::from_secs(value.saturating_mul(3600)),
+        unit => {
+            return Err(format!(
+                "invalid interval unit {unit}, expected ms, s, m or h"
+            ))
+        }
+    };
+    if interval.is_zero() {
+        return Err("the interval can't be zero".to_string());
+    }
+    Ok(interval)
+}
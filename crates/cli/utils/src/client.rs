@@ -1,5 +1,6 @@
 use miden_client::{
-    accounts::{Account, AccountStorageMode, AccountTemplate},
+    accounts::{Account, AccountId, AccountStorageMode, AccountTemplate},
+    auth::AuthSecretKey,
     config::{Endpoint, RpcConfig},
     crypto::{FeltRng, RpoRandomCoin},
     rpc::TonicRpcClient,
@@ -7,18 +8,26 @@ use miden_client::{
         sqlite_store::{config::SqliteStoreConfig, SqliteStore},
         StoreAuthenticator,
     },
+    sync::SyncSummary,
+    transactions::{TransactionId, TransactionRequest, TransactionResult},
     Client, Felt, Word,
 };
 use miden_tx::{LocalTransactionProver, ProvingOptions};
+use pm_accounts::oracle::verify_oracle_account;
 use rand::Rng;
 use std::sync::Arc;
 
+use crate::{CliError, ClientConfig};
+
 // Client Setup
 // ================================================================================================
 
-pub async fn setup_client() -> Client<impl FeltRng> {
-    let store_config = SqliteStoreConfig::default();
-    let store = SqliteStore::new(&store_config).await.unwrap();
+pub async fn setup_client(config: &ClientConfig) -> anyhow::Result<Client<impl FeltRng>> {
+    let mut store_config = SqliteStoreConfig::default();
+    store_config.database_filepath = config.store.to_string_lossy().into_owned();
+    let store = SqliteStore::new(&store_config).await.map_err(|e| {
+        anyhow::anyhow!("Could not open the store {}: {e:?}", config.store.display())
+    })?;
     let store = Arc::new(store);
 
     let mut rng = rand::thread_rng();
@@ -29,20 +38,18 @@ pub async fn setup_client() -> Client<impl FeltRng> {
     let tx_prover = LocalTransactionProver::new(ProvingOptions::default());
 
     let rpc_config = RpcConfig {
-        endpoint: Endpoint::new("http".to_string(), "localhost".to_string(), 57291),
-        timeout_ms: 10000,
+        endpoint: config.endpoint()?,
+        timeout_ms: config.timeout,
     };
 
-    let in_debug_mode = true;
-
-    Client::new(
+    Ok(Client::new(
         Box::new(TonicRpcClient::new(&rpc_config)),
         rng,
         store,
         Arc::new(authenticator),
         Arc::new(tx_prover),
-        in_debug_mode,
-    )
+        config.debug,
+    ))
 }
 
 pub async fn create_wallet(client: &mut Client<impl FeltRng>) -> (Account, Word) {
@@ -52,3 +59,95 @@ pub async fn create_wallet(client: &mut Client<impl FeltRng>) -> (Account, Word)
     };
     client.new_account(wallet_template).await.unwrap()
 }
+
+// Client Calls
+// ================================================================================================
+
+/// Syncs the client with the node.
+pub async fn sync_client(client: &mut Client<impl FeltRng>) -> anyhow::Result<SyncSummary> {
+    Ok(client
+        .sync_state()
+        .await
+        .map_err(|e| CliError::Node(format!("{e:?}")))?)
+}
+
+/// Gets an account tracked by the local store.
+pub async fn get_account(
+    client: &mut Client<impl FeltRng>,
+    account_id: AccountId,
+) -> anyhow::Result<Account> {
+    let (account, _) = client
+        .get_account(account_id)
+        .await
+        .map_err(|e| CliError::AccountNotFound(account_id, format!("{e:?}")))?;
+    Ok(account)
+}
+
+/// Gets an account of the local store, importing it from the node when it isn't tracked yet.
+/// Only public accounts can be imported.
+pub async fn get_or_import_account(
+    client: &mut Client<impl FeltRng>,
+    account_id: AccountId,
+) -> anyhow::Result<Account> {
+    if client.get_account(account_id).await.is_err() {
+        client
+            .import_account_by_id(account_id)
+            .await
+            .map_err(|e| CliError::AccountNotFound(account_id, format!("{e:?}")))?;
+    }
+    get_account(client, account_id).await
+}
+
+/// Public keys of the secret keys held by the accounts of the local store, the only keys the
+/// store authenticator can sign with.
+pub async fn local_public_keys(client: &mut Client<impl FeltRng>) -> anyhow::Result<Vec<Word>> {
+    let account_headers = client
+        .get_account_headers()
+        .await
+        .map_err(|e| anyhow::anyhow!("Could not read the accounts of the store: {e:?}"))?;
+
+    let mut public_keys = Vec::new();
+    for (account_header, _) in account_headers {
+        if let Ok(AuthSecretKey::RpoFalcon512(secret_key)) =
+            client.get_account_auth(account_header.id()).await
+        {
+            public_keys.push(secret_key.public_key().into());
+        }
+    }
+    Ok(public_keys)
+}
+
+/// Executes a transaction against an account, without submitting it.
+pub async fn execute_transaction(
+    client: &mut Client<impl FeltRng>,
+    account_id: AccountId,
+    transaction_request: TransactionRequest,
+) -> anyhow::Result<TransactionResult> {
+    Ok(client
+        .new_transaction(account_id, transaction_request)
+        .await
+        .map_err(|e| CliError::Transaction(format!("{e:?}")))?)
+}
+
+/// Proves & submits an executed transaction to the node, returns the id of the transaction.
+pub async fn submit_transaction(
+    client: &mut Client<impl FeltRng>,
+    tx_result: TransactionResult,
+) -> anyhow::Result<TransactionId> {
+    let tx_id = tx_result.executed_transaction().id();
+    client
+        .submit_transaction(tx_result)
+        .await
+        .map_err(|e| CliError::Transaction(format!("{e:?}")))?;
+    Ok(tx_id)
+}
+
+/// Tracks an oracle deployed elsewhere, checking it exposes the oracle component procedures.
+pub async fn import_oracle(
+    client: &mut Client<impl FeltRng>,
+    oracle_id: AccountId,
+) -> anyhow::Result<Account> {
+    let oracle = get_or_import_account(client, oracle_id).await?;
+    verify_oracle_account(&oracle)?;
+    Ok(oracle)
+}
@@ -0,0 +1,395 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use miden_client::config::Endpoint;
+use serde::{Deserialize, Serialize};
+
+use crate::{OutputFormat, CLIENT_CONFIG_FILE, PRAGMA_ACCOUNTS_STORAGE_FILE, PRAGMA_HOME_DIR};
+
+fn default_rpc_url() -> String {
+    "http://localhost:57291".to_string()
+}
+
+fn default_store() -> PathBuf {
+    PathBuf::from("store.sqlite3")
+}
+
+fn default_accounts() -> PathBuf {
+    PathBuf::from(PRAGMA_ACCOUNTS_STORAGE_FILE)
+}
+
+fn default_timeout() -> u64 {
+    10_000
+}
+
+fn default_debug() -> bool {
+    true
+}
+
+/// Miden networks with a profile under `~/.pragma-miden/<network>/`.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Network {
+    Local,
+    Devnet,
+    Testnet,
+}
+
+impl Network {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Network::Local => "local",
+            Network::Devnet => "devnet",
+            Network::Testnet => "testnet",
+        }
+    }
+
+    pub fn default_rpc_url(&self) -> &'static str {
+        match self {
+            Network::Local => "http://localhost:57291",
+            Network::Devnet => "https://rpc.devnet.miden.io:443",
+            Network::Testnet => "https://rpc.testnet.miden.io:443",
+        }
+    }
+
+    /// Directory of the profile, under `$PM_HOME` when set or `~/.pragma-miden` otherwise.
+    pub fn profile_dir(&self) -> anyhow::Result<PathBuf> {
+        let home = match std::env::var_os("PM_HOME") {
+            Some(home) => PathBuf::from(home),
+            None => std::env::var_os("HOME")
+                .or_else(|| std::env::var_os("USERPROFILE"))
+                .map(|home| PathBuf::from(home).join(PRAGMA_HOME_DIR))
+                .context("Could not find the home directory, set PM_HOME")?,
+        };
+        Ok(home.join(self.name()))
+    }
+}
+
+/// Global flags of the CLIs overriding the client config, also read from the environment.
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct ClientArgs {
+    /// Network profile holding the client config & the accounts, stored in
+    /// `~/.pragma-miden/<network>/`
+    #[clap(long, env = "PM_NETWORK", global = true, conflicts_with = "config")]
+    pub network: Option<Network>,
+    /// Client config file (TOML), `./pragma_miden.toml` is used when it exists
+    #[clap(long = "client-config", env = "PM_CLIENT_CONFIG", global = true)]
+    pub config: Option<PathBuf>,
+    /// URL of the Miden node RPC (example: "https://rpc.testnet.miden.io:443")
+    #[clap(long, env = "PM_RPC_URL", global = true)]
+    pub rpc_url: Option<String>,
+    /// Path of the SQLite store of the client
+    #[clap(long, env = "PM_STORE", global = true)]
+    pub store: Option<PathBuf>,
+    /// Timeout of the RPC requests, in milliseconds
+    #[clap(long, env = "PM_TIMEOUT", global = true)]
+    pub timeout: Option<u64>,
+    /// Executes the transactions in debug mode, printing the `debug` instructions
+    #[clap(
+        long,
+        env = "PM_DEBUG",
+        global = true,
+        num_args = 0..=1,
+        default_missing_value = "true"
+    )]
+    pub debug: Option<bool>,
+    /// Format of the results, `json` prints a single JSON document without decorations and
+    /// disables the debug mode unless `--debug` is set
+    #[clap(long, env = "PM_OUTPUT", global = true, value_enum, default_value_t)]
+    pub output: OutputFormat,
+}
+
+/// Settings of the Miden client used by the CLIs.
+///
+/// Loaded from a TOML file, example:
+/// ```toml
+/// rpc_url = "http://localhost:57291"
+/// store = "store.sqlite3"
+/// timeout = 10000
+/// debug = true
+/// accounts = "pragma_miden.json"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClientConfig {
+    /// URL of the Miden node RPC.
+    #[serde(default = "default_rpc_url")]
+    pub rpc_url: String,
+    /// Path of the SQLite store of the client.
+    #[serde(default = "default_store")]
+    pub store: PathBuf,
+    /// Timeout of the RPC requests, in milliseconds.
+    #[serde(default = "default_timeout")]
+    pub timeout: u64,
+    /// Executes the transactions in debug mode.
+    #[serde(default = "default_debug")]
+    pub debug: bool,
+    /// Path of the registry of the oracle & publisher accounts.
+    #[serde(default = "default_accounts")]
+    pub accounts: PathBuf,
+    /// Format of the printed results, only set from the flags.
+    #[serde(skip)]
+    pub output: OutputFormat,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            rpc_url: default_rpc_url(),
+            store: default_store(),
+            timeout: default_timeout(),
+            debug: default_debug(),
+            accounts: default_accounts(),
+            output: OutputFormat::default(),
+        }
+    }
+}
+
+impl ClientConfig {
+    /// Loads the config file, then applies the flags & environment variables on top of it.
+    pub fn load(args: &ClientArgs) -> anyhow::Result<Self> {
+        let mut config = match (&args.network, &args.config) {
+            (Some(network), _) => Self::from_profile(*network)?,
+            (None, Some(path)) => Self::from_file(path)?,
+            (None, None) if Path::new(CLIENT_CONFIG_FILE).exists() => {
+                Self::from_file(CLIENT_CONFIG_FILE)?
+            }
+            (None, None) => Self::default(),
+        };
+
+        if let Some(rpc_url) = &args.rpc_url {
+            config.rpc_url = rpc_url.clone();
+        }
+        if let Some(store) = &args.store {
+            config.store = store.clone();
+        }
+        if let Some(timeout) = args.timeout {
+            config.timeout = timeout;
+        }
+        // The debug instructions are printed on stdout, mixed with the JSON results
+        if args.output.is_json() {
+            config.debug = false;
+        }
+        if let Some(debug) = args.debug {
+            config.debug = debug;
+        }
+        config.output = args.output;
+
+        config.endpoint()?;
+        Ok(config)
+    }
+
+    /// Loads the config of a network profile, creating the profile with the defaults of the
+    /// network on first use.
+    pub fn from_profile(network: Network) -> anyhow::Result<Self> {
+        let dir = network.profile_dir()?;
+        let path = dir.join(CLIENT_CONFIG_FILE);
+        if !path.exists() {
+            fs::create_dir_all(&dir)
+                .with_context(|| format!("Could not create the profile {}", dir.display()))?;
+            let config = Self {
+                rpc_url: network.default_rpc_url().to_string(),
+                ..Self::default()
+            };
+            fs::write(&path, toml::to_string_pretty(&config)?)
+                .with_context(|| format!("Could not write the client config {}", path.display()))?;
+        }
+        Self::from_file(path)
+    }
+
+    /// Loads the config from a TOML file. Relative store & accounts paths are resolved against
+    /// the directory of the config file.
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Could not read the client config {}", path.display()))?;
+        let mut config: Self = toml::from_str(&content)
+            .with_context(|| format!("Invalid client config {}", path.display()))?;
+
+        if let Some(parent) = path.parent() {
+            if config.store.is_relative() {
+                config.store = parent.join(&config.store);
+            }
+            if config.accounts.is_relative() {
+                config.accounts = parent.join(&config.accounts);
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Parses the RPC URL (format example: "https://rpc.testnet.miden.io:443"), the port
+    /// defaults to the one of the protocol.
+    pub fn endpoint(&self) -> anyhow::Result<Endpoint> {
+        let (protocol, address) = self.rpc_url.split_once("://").ok_or_else(|| {
+            anyhow::anyhow!("Invalid RPC URL {}, missing the protocol", self.rpc_url)
+        })?;
+        let address = address.trim_end_matches('/');
+        let (host, port) = match address.rsplit_once(':') {
+            Some((host, port)) => (
+                host,
+                port.parse()
+                    .with_context(|| format!("Invalid port in the RPC URL {}", self.rpc_url))?,
+            ),
+            None => match protocol {
+                "http" => (address, 80),
+                "https" => (address, 443),
+                _ => anyhow::bail!("Missing port in the RPC URL {}", self.rpc_url),
+            },
+        };
+        if host.is_empty() {
+            anyhow::bail!("Missing host in the RPC URL {}", self.rpc_url);
+        }
+        Ok(Endpoint::new(protocol.to_string(), host.to_string(), port))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    #[derive(clap::Parser)]
+    struct TestCli {
+        #[command(flatten)]
+        client: ClientArgs,
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "pragma_miden_config_{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn endpoint(rpc_url: &str) -> anyhow::Result<String> {
+        let config = ClientConfig {
+            rpc_url: rpc_url.to_string(),
+            ..ClientConfig::default()
+        };
+        Ok(config.endpoint()?.to_string())
+    }
+
+    #[test]
+    fn test_client_config_endpoint() {
+        for (rpc_url, expected) in [
+            (
+                "https://rpc.testnet.miden.io:443",
+                "https://rpc.testnet.miden.io:443",
+            ),
+            ("http://localhost:57291/", "http://localhost:57291"),
+            // The port defaults to the one of the protocol
+            ("http://localhost", "http://localhost:80"),
+            (
+                "https://rpc.devnet.miden.io",
+                "https://rpc.devnet.miden.io:443",
+            ),
+        ] {
+            assert_eq!(endpoint(rpc_url).unwrap(), expected, "{rpc_url}");
+        }
+
+        for rpc_url in [
+            "localhost:57291",
+            "http://localhost:rpc",
+            "http://localhost:99999",
+            "grpc://localhost",
+            "http://:57291",
+        ] {
+            assert!(endpoint(rpc_url).is_err(), "{rpc_url}");
+        }
+    }
+
+    #[test]
+    fn test_client_config_precedence() {
+        let dir = temp_dir("precedence");
+        let path = dir.join(CLIENT_CONFIG_FILE);
+        fs::write(
+            &path,
+            "rpc_url = \"http://file:57291\"\ntimeout = 1000\nstore = \"file.sqlite3\"\n",
+        )
+        .unwrap();
+        let load = |flags: &[&str]| {
+            let args = ["pm-cli", "--client-config", path.to_str().unwrap()]
+                .iter()
+                .chain(flags);
+            ClientConfig::load(&TestCli::parse_from(args).client).unwrap()
+        };
+
+        // The config file is read, its relative paths resolved against its directory
+        let config = load(&[]);
+        assert_eq!(config.rpc_url, "http://file:57291");
+        assert_eq!(config.timeout, 1000);
+        assert_eq!(config.store, dir.join("file.sqlite3"));
+        assert!(config.debug);
+
+        // The environment variables override the config file
+        std::env::set_var("PM_RPC_URL", "http://env:57291");
+        std::env::set_var("PM_TIMEOUT", "2000");
+        let config = load(&[]);
+        assert_eq!(config.rpc_url, "http://env:57291");
+        assert_eq!(config.timeout, 2000);
+
+        // The flags override the environment variables
+        let config = load(&["--rpc-url", "http://flag:57291", "--store", "flag.sqlite3"]);
+        assert_eq!(config.rpc_url, "http://flag:57291");
+        assert_eq!(config.timeout, 2000);
+        assert_eq!(config.store, PathBuf::from("flag.sqlite3"));
+        std::env::remove_var("PM_RPC_URL");
+        std::env::remove_var("PM_TIMEOUT");
+
+        // The JSON output disables the debug mode, unless explicitly enabled
+        assert!(!load(&["--output", "json"]).debug);
+        assert!(load(&["--output", "json", "--debug"]).debug);
+
+        // An invalid RPC URL fails the loading
+        let args = TestCli::parse_from([
+            "pm-cli",
+            "--client-config",
+            path.to_str().unwrap(),
+            "--rpc-url",
+            "localhost",
+        ]);
+        assert!(ClientConfig::load(&args.client).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_client_config_profiles() {
+        let home = temp_dir("profiles");
+        std::env::set_var("PM_HOME", &home);
+
+        // Each profile is created on first use under PM_HOME, with its own store & accounts
+        for network in [Network::Local, Network::Devnet, Network::Testnet] {
+            let profile = home.join(network.name());
+            assert_eq!(network.profile_dir().unwrap(), profile);
+
+            let config = ClientConfig::from_profile(network).unwrap();
+            assert!(profile.join(CLIENT_CONFIG_FILE).exists());
+            assert_eq!(config.rpc_url, network.default_rpc_url());
+            assert_eq!(config.store, profile.join("store.sqlite3"));
+            assert_eq!(config.accounts, profile.join(PRAGMA_ACCOUNTS_STORAGE_FILE));
+        }
+
+        // An existing profile isn't overwritten
+        let devnet_config = home.join("devnet").join(CLIENT_CONFIG_FILE);
+        fs::write(&devnet_config, "rpc_url = \"http://devnet:57291\"\n").unwrap();
+        let args = ClientArgs {
+            network: Some(Network::Devnet),
+            ..ClientArgs::default()
+        };
+        let config = ClientConfig::load(&args).unwrap();
+        assert_eq!(config.rpc_url, "http://devnet:57291");
+        assert_eq!(config.store, home.join("devnet").join("store.sqlite3"));
+
+        std::env::remove_var("PM_HOME");
+        fs::remove_dir_all(&home).unwrap();
+    }
+}
@@ -1,3 +1,3 @@
+pub const PRAGMA_HOME_DIR: &str = ".pragma-miden";
+pub const CLIENT_CONFIG_FILE: &str = "pragma_miden.toml";
 pub const PRAGMA_ACCOUNTS_STORAGE_FILE: &str = "pragma_miden.json";
-pub const ORACLE_ACCOUNT_COLUMN: &str = "oracle_account_id";
-pub const PUBLISHER_ACCOUNT_COLUMN: &str = "publisher_account_id";
@@ -0,0 +1,52 @@
+use miden_client::accounts::AccountId;
+
+/// Exit code of the errors without a more specific code.
+pub const EXIT_FAILURE: i32 = 1;
+
+/// Failures of the CLIs telling the user how to fix them, each kind exits with its own code:
+///
+/// | Code | Failure |
+/// |------|---------|
+/// | 1 | Any other error |
+/// | 2 | Invalid arguments, reported by clap |
+/// | 3 | Missing account: no oracle or publisher initialized, unknown publisher or account |
+/// | 4 | The Miden node can't be reached |
+/// | 5 | A transaction failed to execute or was rejected by the node |
+#[derive(Debug, thiserror::Error)]
+pub enum CliError {
+    #[error(
+        "No oracle initialized, run `pm-oracle-cli init` or `pm-oracle-cli import <ORACLE_ID>`"
+    )]
+    NoOracle,
+    #[error("No publisher initialized, run `pm-publisher-cli init`")]
+    NoPublisher,
+    #[error("Unknown publisher {0}, run `pm-publisher-cli list` to see the registered names")]
+    UnknownPublisher(String),
+    #[error("Account {0} not found, check the id and the network, or run `sync`: {1}")]
+    AccountNotFound(AccountId, String),
+    #[error("Could not reach the Miden node, check the `rpc_url` of the client config: {0}")]
+    Node(String),
+    #[error("Transaction failed: {0}")]
+    Transaction(String),
+}
+
+impl CliError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::NoOracle
+            | CliError::NoPublisher
+            | CliError::UnknownPublisher(_)
+            | CliError::AccountNotFound(..) => 3,
+            CliError::Node(_) => 4,
+            CliError::Transaction(_) => 5,
+        }
+    }
+}
+
+/// Exit code of an error returned by a command.
+pub fn exit_code(error: &anyhow::Error) -> i32 {
+    error
+        .downcast_ref::<CliError>()
+        .map(CliError::exit_code)
+        .unwrap_or(EXIT_FAILURE)
+}
@@ -0,0 +1,218 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::Context;
+use argon2::Argon2;
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    XChaCha20Poly1305, XNonce,
+};
+use colored::*;
+use miden_client::{
+    accounts::{Account, AccountId},
+    crypto::FeltRng,
+    Client,
+};
+use miden_objects::{
+    accounts::AccountData,
+    utils::{Deserializable, Serializable},
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{sync_client, CliError};
+
+/// Version of the key file format written by this CLI.
+pub const KEY_FILE_VERSION: u32 = 1;
+
+/// Environment variable read for the password of the key files instead of prompting for it.
+pub const KEY_PASSWORD_ENV: &str = "PM_KEY_PASSWORD";
+
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 24;
+
+/// Password-encrypted export of an account and its secret key.
+///
+/// The encryption key is derived from the password with Argon2id, the account data is then
+/// encrypted with XChaCha20-Poly1305 so a wrong password or a tampered file is detected.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KeyFile {
+    pub version: u32,
+    /// Id of the exported account, readable without the password.
+    pub account_id: String,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl KeyFile {
+    /// Encrypts the serialized account data of an account with a password.
+    pub fn encrypt(
+        account_id: AccountId,
+        plaintext: &[u8],
+        password: &str,
+    ) -> anyhow::Result<Self> {
+        let mut rng = rand::thread_rng();
+        let salt: [u8; SALT_LENGTH] = rng.gen();
+        let nonce: [u8; NONCE_LENGTH] = rng.gen();
+
+        let cipher = cipher(password, &salt)?;
+        let ciphertext = cipher
+            .encrypt(XNonce::from_slice(&nonce), plaintext)
+            .map_err(|e| anyhow::anyhow!("Could not encrypt the key file: {e}"))?;
+
+        Ok(Self {
+            version: KEY_FILE_VERSION,
+            account_id: account_id.to_string(),
+            salt: hex::encode(salt),
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        })
+    }
+
+    /// Decrypts the serialized account data, failing on a wrong password.
+    pub fn decrypt(&self, password: &str) -> anyhow::Result<Vec<u8>> {
+        if self.version != KEY_FILE_VERSION {
+            anyhow::bail!("Unsupported key file version {}", self.version);
+        }
+        let salt = hex::decode(&self.salt).context("Invalid salt")?;
+        let nonce = hex::decode(&self.nonce).context("Invalid nonce")?;
+        if nonce.len() != NONCE_LENGTH {
+            anyhow::bail!("Invalid nonce length {}", nonce.len());
+        }
+        let ciphertext = hex::decode(&self.ciphertext).context("Invalid ciphertext")?;
+
+        cipher(password, &salt)?
+            .decrypt(XNonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| anyhow::anyhow!("Wrong password or corrupted key file"))
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Could not read the key file {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Invalid key file {}", path.display()))
+    }
+
+    /// Writes the key file, refusing to overwrite an existing file.
+    pub fn write(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        if path.exists() {
+            anyhow::bail!("{} already exists", path.display());
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Could not write the key file {}", path.display()))
+    }
+}
+
+fn cipher(password: &str, salt: &[u8]) -> anyhow::Result<XChaCha20Poly1305> {
+    let mut key = [0_u8; 32];
+    Argon2::default()
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow::anyhow!("Could not derive the key file encryption key: {e}"))?;
+    Ok(XChaCha20Poly1305::new(&key.into()))
+}
+
+/// Reads a 32 bytes account seed, hex encoded, from a file. The seed is secret material, the
+/// Falcon key of the account is derived from it, so a warning is printed when the file can be
+/// read by other users.
+pub fn read_seed_file(path: impl AsRef<Path>) -> anyhow::Result<[u8; 32]> {
+    let path = path.as_ref();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = fs::metadata(path)
+            .with_context(|| format!("Could not read the seed file {}", path.display()))?
+            .permissions()
+            .mode();
+        if mode & 0o077 != 0 {
+            eprintln!(
+                "{}",
+                format!(
+                    "⚠️  The seed file {} is readable by other users (mode {:o}), it derives the \
+                     account key: run `chmod 600 {}`",
+                    path.display(),
+                    mode & 0o777,
+                    path.display()
+                )
+                .bright_yellow()
+            );
+        }
+    }
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Could not read the seed file {}", path.display()))?;
+    let content = content.trim();
+    let seed = hex::decode(content.strip_prefix("0x").unwrap_or(content))
+        .with_context(|| format!("The seed file {} is not hex encoded", path.display()))?;
+    seed.try_into().map_err(|seed: Vec<u8>| {
+        anyhow::anyhow!(
+            "The seed of {} must be 32 bytes, found {}",
+            path.display(),
+            seed.len()
+        )
+    })
+}
+
+/// Reads the password of a key file from `PM_KEY_PASSWORD`, or prompts for it. A new password
+/// is asked twice.
+pub fn read_key_password(confirm: bool) -> anyhow::Result<String> {
+    if let Ok(password) = std::env::var(KEY_PASSWORD_ENV) {
+        return Ok(password);
+    }
+    let password = rpassword::prompt_password("🔑 Key file password: ")?;
+    if confirm {
+        if password.is_empty() {
+            anyhow::bail!("The password can't be empty");
+        }
+        if rpassword::prompt_password("🔑 Confirm the password: ")? != password {
+            anyhow::bail!("The passwords don't match");
+        }
+    }
+    Ok(password)
+}
+
+/// Exports an account of the local store with its secret key to an encrypted key file.
+pub async fn export_account_keys(
+    client: &mut Client<impl FeltRng>,
+    account_id: AccountId,
+    path: &Path,
+    password: &str,
+) -> anyhow::Result<()> {
+    let (account, account_seed) = client
+        .get_account(account_id)
+        .await
+        .map_err(|e| CliError::AccountNotFound(account_id, format!("{e:?}")))?;
+    let auth = client
+        .get_account_auth(account_id)
+        .await
+        .map_err(|e| anyhow::anyhow!("No secret key found for the account {account_id}: {e:?}"))?;
+
+    let account_data = AccountData::new(account, account_seed, auth);
+    KeyFile::encrypt(account_id, &account_data.to_bytes(), password)?.write(path)
+}
+
+/// Imports an account and its secret key from an encrypted key file into the local store.
+pub async fn import_account_keys(
+    client: &mut Client<impl FeltRng>,
+    path: &Path,
+    password: &str,
+) -> anyhow::Result<Account> {
+    let key_file = KeyFile::read(path)?;
+    let account_data = AccountData::read_from_bytes(&key_file.decrypt(password)?)
+        .map_err(|e| anyhow::anyhow!("Invalid account data in {}: {e}", path.display()))?;
+    let account = account_data.account.clone();
+
+    client.import_account(account_data).await.map_err(|e| {
+        anyhow::anyhow!(
+            "Could not import the account {}, is it already in the store? {e:?}",
+            account.id()
+        )
+    })?;
+    // The exported state may be outdated
+    sync_client(client).await?;
+
+    Ok(account)
+}
@@ -0,0 +1,51 @@
+use miden_client::accounts::AccountId;
+use pm_types::Entry;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::exit_code;
+
+/// Format of the results printed by the CLIs.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Colored text, tables & banners
+    #[default]
+    Text,
+    /// A single JSON document on stdout, without any decoration
+    Json,
+}
+
+impl OutputFormat {
+    pub fn is_json(&self) -> bool {
+        *self == OutputFormat::Json
+    }
+}
+
+/// Prints the result of a command as a single line of JSON on stdout.
+pub fn print_json(value: &impl Serialize) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string(value)?);
+    Ok(())
+}
+
+/// Reports the error of a failed command, as `{"error": ..., "code": ...}` on stdout with the
+/// JSON output, then exits with the code of the error (see [`crate::CliError`]).
+pub fn exit_with_error(output: OutputFormat, error: &anyhow::Error) -> ! {
+    let code = exit_code(error);
+    if output.is_json() {
+        println!("{}", json!({ "error": format!("{error:#}"), "code": code }));
+    } else {
+        eprintln!("Error: {error:?}");
+    }
+    std::process::exit(code)
+}
+
+/// JSON representation of an entry, the price being kept as an integer with its decimals.
+pub fn entry_json(publisher_id: AccountId, entry: &Entry) -> Value {
+    json!({
+        "publisher_id": publisher_id.to_string(),
+        "pair": entry.pair.to_string(),
+        "price": entry.price,
+        "decimals": entry.decimals,
+        "timestamp": entry.timestamp,
+    })
+}
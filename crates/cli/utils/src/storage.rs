@@ -1,81 +1,396 @@
 use std::collections::HashMap;
-use std::fs;
-use std::path::Path;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
+use anyhow::Context;
+use fs2::FileExt;
+use miden_client::accounts::AccountId;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::CliError;
+
+/// Version of the schema written by this CLI.
+pub const STORAGE_VERSION: u32 = 2;
+
+/// Name given to a default publisher stored before publishers were named.
+const UNNAMED_PUBLISHER: &str = "default";
+
+// Keys of the version 1 storage, a flat map of strings.
+const V1_ORACLE_ACCOUNT_KEY: &str = "oracle_account_id";
+const V1_PUBLISHER_ACCOUNT_KEY: &str = "publisher_account_id";
+const V1_PUBLISHERS_KEY: &str = "publishers";
+const V1_PAIRS_KEY: &str = "pairs";
+
+/// Publisher account of the registry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublisherRecord {
+    pub name: String,
+    pub id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 struct Storage {
-    data: HashMap<String, String>,
+    version: u32,
+    #[serde(default)]
+    oracle_id: Option<String>,
+    /// Publisher used when none is specified.
+    #[serde(default)]
+    default_publisher: Option<String>,
+    #[serde(default)]
+    publishers: Vec<PublisherRecord>,
+    /// Pairs supported by the oracle.
+    #[serde(default)]
+    pairs: Vec<String>,
+}
+
+impl Default for Storage {
+    fn default() -> Self {
+        Self {
+            version: STORAGE_VERSION,
+            oracle_id: None,
+            default_publisher: None,
+            publishers: Vec::new(),
+            pairs: Vec::new(),
+        }
+    }
 }
 
+/// Registry of the oracle & publisher accounts, stored as JSON.
+///
+/// Writes go to a temporary file renamed over the registry, so a crash never leaves a
+/// truncated file, and are done under an exclusive advisory lock on `<file>.lock` after
+/// reloading the registry, so concurrent CLI invocations don't lose each other's updates.
 pub struct JsonStorage {
-    file_path: String,
+    file_path: PathBuf,
     storage: Storage,
 }
 
 impl JsonStorage {
     /// Creates a new storage instance, loading from file if it exists
-    pub fn new(file_name: &str) -> anyhow::Result<Self> {
-        let file_path = format!("./{}", file_name);
-        let storage = if Path::new(&file_path).exists() {
-            let content = fs::read_to_string(&file_path)?;
-            serde_json::from_str(&content)?
-        } else {
-            Storage {
-                data: HashMap::new(),
-            }
+    pub fn new(file_path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let file_path = file_path.as_ref().to_path_buf();
+        let storage = {
+            let _lock = StorageLock::shared(&file_path)?;
+            Self::read(&file_path)?
         };
 
         Ok(Self { file_path, storage })
     }
 
     /// Creates a new storage file, returns error if it already exists
-    pub fn create(file_name: &str) -> anyhow::Result<Self> {
-        let file_path = format!("./{}", file_name);
-        if Path::new(&file_path).exists() {
+    pub fn create(file_path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let file_path = file_path.as_ref().to_path_buf();
+        let _lock = StorageLock::exclusive(&file_path)?;
+        if file_path.exists() {
             anyhow::bail!("Storage file already exists");
         }
 
-        let storage = Storage {
-            data: HashMap::new(),
+        let instance = Self {
+            file_path,
+            storage: Storage::default(),
         };
-
-        let instance = Self { file_path, storage };
-        instance.save()?;
+        instance.write()?;
 
         Ok(instance)
     }
 
     /// Checks if a storage file exists
-    pub fn exists(file_name: &str) -> bool {
-        let file_path = format!("./{}", file_name);
-        Path::new(&file_path).exists()
+    pub fn exists(file_path: impl AsRef<Path>) -> bool {
+        file_path.as_ref().exists()
     }
 
     /// Deletes the storage file
     pub fn delete(&self) -> anyhow::Result<()> {
-        if Path::new(&self.file_path).exists() {
+        let _lock = StorageLock::exclusive(&self.file_path)?;
+        if self.file_path.exists() {
             fs::remove_file(&self.file_path)?;
         }
         Ok(())
     }
 
-    /// Adds or updates a key-value pair
-    pub fn add_key(&mut self, key: &str, value: &str) -> anyhow::Result<()> {
-        self.storage.data.insert(key.to_string(), value.to_string());
-        self.save()
+    /// Path of the storage file
+    pub fn path(&self) -> &Path {
+        &self.file_path
+    }
+
+    /// Id of the oracle account
+    pub fn oracle_id(&self) -> anyhow::Result<AccountId> {
+        let id = self
+            .storage
+            .oracle_id
+            .as_deref()
+            .ok_or(CliError::NoOracle)?;
+        parse_account_id(id)
+    }
+
+    /// Sets the id of the oracle account
+    pub fn set_oracle_id(&mut self, id: AccountId) -> anyhow::Result<()> {
+        self.update(|storage| {
+            storage.oracle_id = Some(id.to_string());
+            Ok(())
+        })
+    }
+
+    /// Registered publishers, in registration order
+    pub fn publishers(&self) -> &[PublisherRecord] {
+        &self.storage.publishers
+    }
+
+    /// Id of the publisher used when none is specified
+    pub fn default_publisher(&self) -> Option<&str> {
+        self.storage.default_publisher.as_deref()
+    }
+
+    /// Registers a named publisher, the first one registered becomes the default publisher
+    pub fn add_publisher(&mut self, name: &str, id: AccountId) -> anyhow::Result<()> {
+        if name.is_empty() || name.starts_with("0x") {
+            anyhow::bail!("Invalid publisher name {name}");
+        }
+        let id = id.to_string();
+        self.update(|storage| {
+            if storage
+                .publishers
+                .iter()
+                .any(|publisher| publisher.name == name)
+            {
+                anyhow::bail!("A publisher named {name} already exists");
+            }
+            if storage
+                .publishers
+                .iter()
+                .any(|publisher| publisher.id == id)
+            {
+                anyhow::bail!("Publisher {id} is already registered");
+            }
+            storage.publishers.push(PublisherRecord {
+                name: name.to_string(),
+                id: id.clone(),
+            });
+            storage.default_publisher.get_or_insert(id);
+            Ok(())
+        })
+    }
+
+    /// Resolves a publisher given by name or by id, the default publisher when none is given.
+    /// Ids of publishers missing from the registry are accepted.
+    pub fn publisher_id(&self, publisher: Option<&str>) -> anyhow::Result<AccountId> {
+        let id = match publisher {
+            None => self.default_publisher().ok_or(CliError::NoPublisher)?,
+            Some(publisher) if publisher.starts_with("0x") => publisher,
+            Some(publisher) => self
+                .publishers()
+                .iter()
+                .find(|record| record.name == publisher)
+                .map(|record| record.id.as_str())
+                .ok_or_else(|| CliError::UnknownPublisher(publisher.to_string()))?,
+        };
+        parse_account_id(id)
+    }
+
+    /// Pairs supported by the oracle
+    pub fn pairs(&self) -> &[String] {
+        &self.storage.pairs
+    }
+
+    /// Replaces the pairs supported by the oracle
+    pub fn set_pairs(&mut self, pairs: Vec<String>) -> anyhow::Result<()> {
+        self.update(|storage| {
+            storage.pairs = pairs;
+            Ok(())
+        })
+    }
+
+    /// Adds a pair supported by the oracle, if missing
+    pub fn add_pair(&mut self, pair: &str) -> anyhow::Result<()> {
+        self.update(|storage| {
+            if !storage.pairs.iter().any(|stored_pair| stored_pair == pair) {
+                storage.pairs.push(pair.to_string());
+            }
+            Ok(())
+        })
+    }
+
+    /// Removes a pair supported by the oracle
+    pub fn remove_pair(&mut self, pair: &str) -> anyhow::Result<()> {
+        self.update(|storage| {
+            storage.pairs.retain(|stored_pair| stored_pair != pair);
+            Ok(())
+        })
     }
 
-    /// Retrieves a value by key
-    pub fn get_key(&self, key: &str) -> Option<&String> {
-        self.storage.data.get(key)
+    /// Applies a change to the latest state of the file, under an exclusive lock
+    fn update(
+        &mut self,
+        change: impl FnOnce(&mut Storage) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        let _lock = StorageLock::exclusive(&self.file_path)?;
+        let mut storage = Self::read(&self.file_path)?;
+        change(&mut storage)?;
+        self.storage = storage;
+        self.write()
     }
 
-    /// Saves the current state to file
-    fn save(&self) -> anyhow::Result<()> {
+    /// Reads the storage file, migrating it to the current schema
+    fn read(file_path: &Path) -> anyhow::Result<Storage> {
+        if !file_path.exists() {
+            return Ok(Storage::default());
+        }
+        let content = fs::read_to_string(file_path)
+            .with_context(|| format!("Could not read {}", file_path.display()))?;
+        let value: Value = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid account registry {}", file_path.display()))?;
+        migrate(value).with_context(|| format!("Invalid account registry {}", file_path.display()))
+    }
+
+    /// Writes the state to a temporary file, then renames it over the storage file
+    fn write(&self) -> anyhow::Result<()> {
+        if let Some(parent) = self.file_path.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent)?;
+            }
+        }
         let content = serde_json::to_string_pretty(&self.storage)?;
-        fs::write(&self.file_path, content)?;
+
+        let tmp_path = sibling_path(&self.file_path, "tmp");
+        let mut tmp_file = File::create(&tmp_path)
+            .with_context(|| format!("Could not write {}", tmp_path.display()))?;
+        tmp_file.write_all(content.as_bytes())?;
+        tmp_file.sync_all()?;
+        fs::rename(&tmp_path, &self.file_path)
+            .with_context(|| format!("Could not write {}", self.file_path.display()))?;
         Ok(())
     }
 }
+
+/// Advisory lock on `<file>.lock`, released when dropped. The storage file itself can't be
+/// locked since it's replaced on every write.
+struct StorageLock(File);
+
+impl StorageLock {
+    fn shared(file_path: &Path) -> anyhow::Result<Self> {
+        let lock = Self::open(file_path)?;
+        lock.0.lock_shared()?;
+        Ok(lock)
+    }
+
+    fn exclusive(file_path: &Path) -> anyhow::Result<Self> {
+        let lock = Self::open(file_path)?;
+        lock.0.lock_exclusive()?;
+        Ok(lock)
+    }
+
+    fn open(file_path: &Path) -> anyhow::Result<Self> {
+        if let Some(parent) = file_path.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+        let lock_path = sibling_path(file_path, "lock");
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .with_context(|| format!("Could not open the lock {}", lock_path.display()))?;
+        Ok(Self(file))
+    }
+}
+
+impl Drop for StorageLock {
+    fn drop(&mut self) {
+        let _ = self.0.unlock();
+    }
+}
+
+fn sibling_path(file_path: &Path, extension: &str) -> PathBuf {
+    let mut path = file_path.as_os_str().to_owned();
+    path.push(".");
+    path.push(extension);
+    PathBuf::from(path)
+}
+
+fn parse_account_id(id: &str) -> anyhow::Result<AccountId> {
+    AccountId::from_hex(id).map_err(|e| anyhow::anyhow!("Invalid account id {id}: {e}"))
+}
+
+/// Upgrades a storage of any known version to the current schema.
+fn migrate(mut value: Value) -> anyhow::Result<Storage> {
+    loop {
+        // The version 1 storage has no version field
+        let version = match value.get("version") {
+            Some(version) => version
+                .as_u64()
+                .ok_or_else(|| anyhow::anyhow!("Invalid version {version}"))?,
+            None => 1,
+        };
+        value = match version {
+            1 => migrate_v1(value)?,
+            v if v == STORAGE_VERSION as u64 => return Ok(serde_json::from_value(value)?),
+            v => anyhow::bail!(
+                "Unsupported version {v}, the registry was written by a newer CLI (version {} \
+                 supported)",
+                STORAGE_VERSION
+            ),
+        };
+    }
+}
+
+/// Version 1: `{"data": {"oracle_account_id": .., "publisher_account_id": ..,
+/// "publishers": "name=id,..", "pairs": "BTC/USD,.."}}`
+fn migrate_v1(value: Value) -> anyhow::Result<Value> {
+    #[derive(Deserialize)]
+    struct StorageV1 {
+        data: HashMap<String, String>,
+    }
+    let StorageV1 { data } = serde_json::from_value(value)?;
+
+    let mut publishers: Vec<PublisherRecord> = data
+        .get(V1_PUBLISHERS_KEY)
+        .map(|publishers| {
+            publishers
+                .split(',')
+                .filter_map(|publisher| publisher.split_once('='))
+                .map(|(name, id)| PublisherRecord {
+                    name: name.to_string(),
+                    id: id.to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let default_publisher = data.get(V1_PUBLISHER_ACCOUNT_KEY).cloned();
+    if let Some(default_id) = &default_publisher {
+        if !publishers
+            .iter()
+            .any(|publisher| publisher.id == *default_id)
+        {
+            publishers.insert(
+                0,
+                PublisherRecord {
+                    name: UNNAMED_PUBLISHER.to_string(),
+                    id: default_id.clone(),
+                },
+            );
+        }
+    }
+
+    let storage = Storage {
+        version: 2,
+        oracle_id: data.get(V1_ORACLE_ACCOUNT_KEY).cloned(),
+        default_publisher,
+        publishers,
+        pairs: data
+            .get(V1_PAIRS_KEY)
+            .map(|pairs| {
+                pairs
+                    .split(',')
+                    .filter(|pair| !pair.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default(),
+    };
+    Ok(serde_json::to_value(storage)?)
+}
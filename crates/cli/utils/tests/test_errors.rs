@@ -0,0 +1,49 @@
+use std::time::Duration;
+
+use pm_utils_cli::{
+    exit_code, parse_account_id, parse_interval, parse_pair, parse_public_key, CliError,
+    JsonStorage, EXIT_FAILURE,
+};
+
+#[test]
+fn test_argument_parsers() {
+    assert!(parse_account_id("0x800000000000001f").is_ok());
+    assert!(parse_account_id("800000000000001f").is_err());
+    assert!(parse_account_id("0xzz").is_err());
+
+    assert_eq!(parse_pair("BTC/USD").unwrap().to_string(), "BTC/USD");
+    assert!(parse_pair("BTCUSD").is_err());
+
+    assert!(parse_public_key("0x1234").is_err());
+
+    assert_eq!(parse_interval("500ms").unwrap(), Duration::from_millis(500));
+    assert_eq!(parse_interval("5s").unwrap(), Duration::from_secs(5));
+    assert_eq!(parse_interval("5").unwrap(), Duration::from_secs(5));
+    assert_eq!(parse_interval("2m").unwrap(), Duration::from_secs(120));
+    assert!(parse_interval("0s").is_err());
+    assert!(parse_interval("5d").is_err());
+    assert!(parse_interval("s").is_err());
+}
+
+#[test]
+fn test_exit_codes() {
+    let path =
+        std::env::temp_dir().join(format!("pragma_miden_errors_{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let storage = JsonStorage::new(&path).unwrap();
+
+    assert_eq!(exit_code(&storage.oracle_id().unwrap_err()), 3);
+    assert_eq!(exit_code(&storage.publisher_id(None).unwrap_err()), 3);
+    assert_eq!(
+        exit_code(&storage.publisher_id(Some("alice")).unwrap_err()),
+        3
+    );
+    assert_eq!(exit_code(&CliError::Node("timeout".to_string()).into()), 4);
+    assert_eq!(
+        exit_code(&CliError::Transaction("rejected".to_string()).into()),
+        5
+    );
+    assert_eq!(exit_code(&anyhow::anyhow!("unexpected")), EXIT_FAILURE);
+
+    storage.delete().unwrap();
+}
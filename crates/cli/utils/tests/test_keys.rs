@@ -0,0 +1,41 @@
+use miden_client::accounts::AccountId;
+use pm_utils_cli::{read_seed_file, KeyFile, KEY_FILE_VERSION};
+
+const ACCOUNT_ID: &str = "0x800000000000001f";
+
+#[test]
+fn test_key_file_round_trip() {
+    let account_id = AccountId::from_hex(ACCOUNT_ID).unwrap();
+    let plaintext = b"serialized account data";
+
+    let key_file = KeyFile::encrypt(account_id, plaintext, "hunter2").unwrap();
+    assert_eq!(key_file.version, KEY_FILE_VERSION);
+    assert_eq!(key_file.account_id, ACCOUNT_ID);
+
+    let path = std::env::temp_dir().join(format!("pragma_miden_{}.key", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    key_file.write(&path).unwrap();
+    // Existing key files are never overwritten
+    assert!(key_file.write(&path).is_err());
+
+    let key_file = KeyFile::read(&path).unwrap();
+    assert_eq!(key_file.decrypt("hunter2").unwrap(), plaintext);
+    assert!(key_file.decrypt("hunter3").is_err());
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_read_seed_file() {
+    let path = std::env::temp_dir().join(format!("pragma_miden_{}.seed", std::process::id()));
+
+    std::fs::write(&path, format!("0x{}\n", "2a".repeat(32))).unwrap();
+    assert_eq!(read_seed_file(&path).unwrap(), [0x2a; 32]);
+
+    std::fs::write(&path, "2a".repeat(31)).unwrap();
+    assert!(read_seed_file(&path).is_err());
+    std::fs::write(&path, "not a seed").unwrap();
+    assert!(read_seed_file(&path).is_err());
+
+    std::fs::remove_file(&path).unwrap();
+}
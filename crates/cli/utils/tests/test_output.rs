@@ -0,0 +1,49 @@
+use std::str::FromStr;
+
+use miden_client::accounts::AccountId;
+use pm_types::{Entry, Pair};
+use pm_utils_cli::{entry_json, ClientArgs, ClientConfig, OutputFormat};
+
+#[test]
+fn test_json_output_disables_debug() {
+    let args = ClientArgs {
+        output: OutputFormat::Json,
+        ..ClientArgs::default()
+    };
+    let config = ClientConfig::load(&args).unwrap();
+    assert!(config.output.is_json());
+    assert!(!config.debug);
+
+    // An explicit debug flag still wins
+    let args = ClientArgs {
+        output: OutputFormat::Json,
+        debug: Some(true),
+        ..ClientArgs::default()
+    };
+    assert!(ClientConfig::load(&args).unwrap().debug);
+
+    let config = ClientConfig::load(&ClientArgs::default()).unwrap();
+    assert_eq!(config.output, OutputFormat::Text);
+}
+
+#[test]
+fn test_entry_json() {
+    let publisher_id = AccountId::from_hex("0x800000000000001f").unwrap();
+    let entry = Entry {
+        pair: Pair::from_str("BTC/USD").unwrap(),
+        price: 9758812,
+        decimals: 2,
+        timestamp: 1733844099,
+    };
+
+    assert_eq!(
+        entry_json(publisher_id, &entry),
+        serde_json::json!({
+            "publisher_id": "0x800000000000001f",
+            "pair": "BTC/USD",
+            "price": 9758812,
+            "decimals": 2,
+            "timestamp": 1733844099,
+        })
+    );
+}
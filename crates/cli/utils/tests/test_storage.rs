@@ -0,0 +1,139 @@
+use std::path::PathBuf;
+
+use miden_client::accounts::AccountId;
+use pm_utils_cli::{JsonStorage, PublisherRecord, STORAGE_VERSION};
+
+const ORACLE_ID: &str = "0x800000000000000f";
+const ALICE_ID: &str = "0x800000000000001f";
+const BOB_ID: &str = "0x800000000000002f";
+
+fn storage_path(name: &str) -> PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("pragma_miden_{}_{}.json", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn account_id(id: &str) -> AccountId {
+    AccountId::from_hex(id).unwrap()
+}
+
+fn publisher(name: &str, id: &str) -> PublisherRecord {
+    PublisherRecord {
+        name: name.to_string(),
+        id: id.to_string(),
+    }
+}
+
+#[test]
+fn test_storage_named_publishers() {
+    let path = storage_path("publishers");
+    let mut storage = JsonStorage::new(&path).unwrap();
+    assert!(storage.publisher_id(None).is_err());
+    assert!(storage.oracle_id().is_err());
+
+    storage.set_oracle_id(account_id(ORACLE_ID)).unwrap();
+    storage
+        .add_publisher("alice", account_id(ALICE_ID))
+        .unwrap();
+    storage.add_publisher("bob", account_id(BOB_ID)).unwrap();
+    assert!(storage.add_publisher("alice", account_id(BOB_ID)).is_err());
+    assert!(storage
+        .add_publisher("carol", account_id(ALICE_ID))
+        .is_err());
+    assert!(storage
+        .add_publisher("0xcarol", account_id(ORACLE_ID))
+        .is_err());
+
+    // The first publisher stays the default one, the registry is persisted
+    let storage = JsonStorage::new(&path).unwrap();
+    assert_eq!(storage.oracle_id().unwrap(), account_id(ORACLE_ID));
+    assert_eq!(
+        storage.publishers(),
+        [publisher("alice", ALICE_ID), publisher("bob", BOB_ID)]
+    );
+    assert_eq!(storage.publisher_id(None).unwrap(), account_id(ALICE_ID));
+    assert_eq!(
+        storage.publisher_id(Some("bob")).unwrap(),
+        account_id(BOB_ID)
+    );
+    assert_eq!(
+        storage.publisher_id(Some(BOB_ID)).unwrap(),
+        account_id(BOB_ID)
+    );
+    assert!(storage.publisher_id(Some("carol")).is_err());
+
+    storage.delete().unwrap();
+}
+
+#[test]
+fn test_storage_migrates_v1() {
+    let path = storage_path("v1");
+    std::fs::write(
+        &path,
+        format!(
+            r#"{{"data": {{"oracle_account_id": "{ORACLE_ID}", "publisher_account_id": "{ALICE_ID}", "publishers": "bob={BOB_ID}", "pairs": "BTC/USD,ETH/USD"}}}}"#
+        ),
+    )
+    .unwrap();
+
+    let mut storage = JsonStorage::new(&path).unwrap();
+    assert_eq!(storage.oracle_id().unwrap(), account_id(ORACLE_ID));
+    assert_eq!(
+        storage.publishers(),
+        [publisher("default", ALICE_ID), publisher("bob", BOB_ID)]
+    );
+    assert_eq!(storage.publisher_id(None).unwrap(), account_id(ALICE_ID));
+    assert_eq!(storage.pairs(), ["BTC/USD", "ETH/USD"]);
+
+    // The migrated registry is written with the current schema
+    storage.remove_pair("BTC/USD").unwrap();
+    let content: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(content["version"], STORAGE_VERSION);
+    assert_eq!(content["pairs"], serde_json::json!(["ETH/USD"]));
+
+    storage.delete().unwrap();
+}
+
+#[test]
+fn test_storage_rejects_newer_version() {
+    let path = storage_path("newer_version");
+    std::fs::write(&path, r#"{"version": 99}"#).unwrap();
+    assert!(JsonStorage::new(&path).is_err());
+
+    std::fs::write(&path, r#"{"version": 2, "oracle_id": "#).unwrap();
+    assert!(JsonStorage::new(&path).is_err());
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_storage_concurrent_updates() {
+    let path = storage_path("concurrent");
+    let pairs = [
+        "BTC/USD", "ETH/USD", "SOL/USD", "DOGE/USD", "XRP/USD", "ADA/USD",
+    ];
+
+    // Each writer holds a stale copy of the registry, the updates are applied on the latest
+    // state of the file
+    let mut writers: Vec<JsonStorage> = pairs
+        .iter()
+        .map(|_| JsonStorage::new(&path).unwrap())
+        .collect();
+    std::thread::scope(|scope| {
+        for (storage, pair) in writers.iter_mut().zip(pairs) {
+            scope.spawn(move || storage.add_pair(pair).unwrap());
+        }
+    });
+
+    let storage = JsonStorage::new(&path).unwrap();
+    let mut stored_pairs = storage.pairs().to_vec();
+    stored_pairs.sort();
+    let mut expected_pairs = pairs.map(String::from).to_vec();
+    expected_pairs.sort();
+    assert_eq!(stored_pairs, expected_pairs);
+    assert!(!path.with_extension("json.tmp").exists());
+
+    storage.delete().unwrap();
+}
@@ -1,7 +1,11 @@
 pub mod currency;
 pub mod entry;
+pub mod median;
 pub mod pair;
+pub mod publisher_id;
 
 pub use currency::*;
 pub use entry::*;
+pub use median::*;
 pub use pair::*;
+pub use publisher_id::*;
@@ -0,0 +1,12 @@
+/// Median of the prices, computed like the oracle `ram_get_median` procedure: the mean of the two
+/// middle prices, rounded down, for an even number of prices. `None` without any price.
+pub fn median(prices: &[u64]) -> Option<u64> {
+    let mut prices = prices.to_vec();
+    prices.sort_unstable();
+    let middle = prices.len() / 2;
+    match prices.len() {
+        0 => None,
+        len if len % 2 == 1 => Some(prices[middle]),
+        _ => Some(((prices[middle - 1] as u128 + prices[middle] as u128) / 2) as u64),
+    }
+}
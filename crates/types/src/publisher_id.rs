@@ -0,0 +1,88 @@
+use std::str::FromStr;
+
+use miden_crypto::Felt;
+use miden_crypto::Word;
+use miden_crypto::ZERO;
+use miden_objects::accounts::AccountId;
+
+/// Id of a publisher account, as stored in the oracle & passed to its procedures.
+///
+/// The canonical word encoding is `[0, 0, 0, id]`, the oracle procedures reading the id from the
+/// last element of the word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PublisherId(AccountId);
+
+impl PublisherId {
+    pub fn new(account_id: AccountId) -> Self {
+        Self(account_id)
+    }
+
+    pub fn account_id(&self) -> AccountId {
+        self.0
+    }
+
+    pub fn to_word(&self) -> Word {
+        [ZERO, ZERO, ZERO, self.0.into()]
+    }
+
+    /// Decodes a `[0, 0, 0, id]` word, failing on any other layout or an invalid account id.
+    pub fn from_word(word: Word) -> anyhow::Result<Self> {
+        if word[..3] != [ZERO, ZERO, ZERO] {
+            return Err(anyhow::anyhow!(
+                "Invalid publisher id word {:?}, expected [0, 0, 0, id]",
+                word.map(|felt| felt.as_int())
+            ));
+        }
+        let account_id = AccountId::try_from(word[3].as_int())
+            .map_err(|e| anyhow::anyhow!("Invalid publisher id {}: {e}", word[3].as_int()))?;
+        Ok(Self(account_id))
+    }
+}
+
+impl From<AccountId> for PublisherId {
+    fn from(account_id: AccountId) -> Self {
+        Self(account_id)
+    }
+}
+
+impl From<PublisherId> for AccountId {
+    fn from(publisher_id: PublisherId) -> Self {
+        publisher_id.0
+    }
+}
+
+impl From<PublisherId> for Felt {
+    fn from(publisher_id: PublisherId) -> Self {
+        publisher_id.0.into()
+    }
+}
+
+impl From<PublisherId> for Word {
+    fn from(publisher_id: PublisherId) -> Self {
+        publisher_id.to_word()
+    }
+}
+
+impl TryFrom<Word> for PublisherId {
+    type Error = anyhow::Error;
+
+    fn try_from(word: Word) -> anyhow::Result<Self> {
+        Self::from_word(word)
+    }
+}
+
+impl FromStr for PublisherId {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let account_id =
+            AccountId::from_hex(s).map_err(|e| anyhow::anyhow!("Invalid publisher id {s}: {e}"))?;
+        Ok(Self(account_id))
+    }
+}
+
+impl std::fmt::Display for PublisherId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,29 @@
+use std::str::FromStr;
+
+use miden_crypto::{Felt, ZERO};
+use miden_objects::accounts::AccountId;
+use pm_types::PublisherId;
+
+const PUBLISHER_ID: &str = "0x800000000000001f";
+
+#[test]
+fn test_publisher_id_word_encoding() {
+    let account_id = AccountId::from_hex(PUBLISHER_ID).unwrap();
+    let publisher_id = PublisherId::from(account_id);
+
+    let word = publisher_id.to_word();
+    assert_eq!(word, [ZERO, ZERO, ZERO, account_id.into()]);
+    assert_eq!(PublisherId::from_word(word).unwrap(), publisher_id);
+    assert_eq!(AccountId::from(publisher_id), account_id);
+
+    // The id must be the last element of the word
+    assert!(PublisherId::from_word([account_id.into(), ZERO, ZERO, ZERO]).is_err());
+    assert!(PublisherId::from_word([ZERO, ZERO, ZERO, Felt::new(1)]).is_err());
+}
+
+#[test]
+fn test_publisher_id_string() {
+    let publisher_id = PublisherId::from_str(PUBLISHER_ID).unwrap();
+    assert_eq!(publisher_id.to_string(), PUBLISHER_ID);
+    assert!(PublisherId::from_str("800000000000001f").is_err());
+}